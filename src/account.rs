@@ -7,14 +7,19 @@ use iota_wallet::account::AccountHandle;
 
 use crate::{
     command::account::{
-        addresses_command, balance_command, burn_native_token_command, burn_nft_command, claim_command,
-        consolidate_command, create_alias_outputs_command, decrease_native_token_command,
-        decrease_voting_power_command, destroy_alias_command, destroy_foundry_command, faucet_command,
-        increase_native_token_command, increase_voting_power_command, mint_native_token_command, mint_nft_command,
-        new_address_command, output_command, outputs_command, participation_overview_command, send_command,
-        send_micro_command, send_native_token_command, send_nft_command, stop_participating_command, sync_command,
-        transactions_command, unspent_outputs_command, vote_command, voting_output_command, voting_power_command,
-        AccountCli, AccountCommand,
+        addresses_command, alias_set_governor_command, alias_set_state_controller_command, alias_update_state_command,
+        auto_claim_command, balance_command, burn_native_token_command, burn_nft_command, claim_command,
+        claimable_command, consolidate_command, contacts_command, create_alias_outputs_command,
+        decrease_native_token_command, decrease_voting_power_command, destroy_alias_command, destroy_foundry_command,
+        export_transactions_command, faucet_command, increase_native_token_command, increase_voting_power_command,
+        label_address_command, lock_output_command, mint_native_token_command, mint_nft_command, new_address_command,
+        note_transaction_command, output_command, outputs_command, participation_overview_command, pending_command,
+        reattach_command, reject_command, retry_command, send_batch_command, send_command, send_micro_command,
+        send_native_token_command, send_nft_command, send_output_command, send_outputs_command,
+        sign_transaction_command, stop_participating_command, storage_deposit_command, submit_transaction_command,
+        sweep_command, sync_command, transaction_command, transactions_command, unlock_output_command,
+        unspent_outputs_command, vote_command, voting_output_command, voting_power_command, wait_transaction_command,
+        AccountCli, AccountCommand, TransactionFlags,
     },
     error::Error,
     helper::bytes_from_hex_or_file,
@@ -63,29 +68,119 @@ pub async fn account_prompt_internal(account_handle: AccountHandle) -> Result<bo
                     return Ok(false);
                 }
             };
-            if let Err(err) = match account_cli.command {
+            // `prepare-transaction` runs another command, writing its transaction to a file instead of signing it.
+            let (command, transaction_flags) = match account_cli.command {
+                AccountCommand::PrepareTransaction { path, command } => {
+                    let account_cli = match AccountCli::try_parse_from(
+                        std::iter::once("Account:").chain(command.iter().map(String::as_str)),
+                    ) {
+                        Ok(account_cli) => account_cli,
+                        Err(err) => {
+                            println!("{err}");
+                            return Ok(false);
+                        }
+                    };
+                    (
+                        account_cli.command,
+                        TransactionFlags {
+                            prepared_transaction_path: Some(path),
+                            ..account_cli.transaction_flags
+                        },
+                    )
+                }
+                command => (command, account_cli.transaction_flags),
+            };
+            if !(transaction_flags.inputs.is_empty() && transaction_flags.mandatory_inputs.is_empty())
+                && !command.supports_input_selection()
+            {
+                log::error!("this command doesn't support `--inputs` nor `--mandatory-inputs`");
+                return Ok(false);
+            }
+            if let Err(err) = match command {
                 AccountCommand::Addresses => addresses_command(&account_handle).await,
-                AccountCommand::Balance => balance_command(&account_handle).await,
+                AccountCommand::AliasSetGovernor { alias_id, address } => {
+                    alias_set_governor_command(&account_handle, alias_id, address, &transaction_flags).await
+                }
+                AccountCommand::AliasSetStateController { alias_id, address } => {
+                    alias_set_state_controller_command(&account_handle, alias_id, address, &transaction_flags).await
+                }
+                AccountCommand::AliasUpdateState {
+                    alias_id,
+                    state_metadata_hex,
+                    state_metadata_file,
+                    clear,
+                } => {
+                    alias_update_state_command(
+                        &account_handle,
+                        alias_id,
+                        bytes_from_hex_or_file(state_metadata_hex, state_metadata_file).await?,
+                        clear,
+                        &transaction_flags,
+                    )
+                    .await
+                }
+                AccountCommand::AutoClaim { window, off } => auto_claim_command(&account_handle, window, off).await,
+                AccountCommand::Balance { json } => balance_command(&account_handle, json).await,
                 AccountCommand::BurnNativeToken { token_id, amount } => {
-                    burn_native_token_command(&account_handle, token_id, amount).await
+                    burn_native_token_command(&account_handle, token_id, amount, &transaction_flags).await
+                }
+                AccountCommand::BurnNft { nft_id } => {
+                    burn_nft_command(&account_handle, nft_id, &transaction_flags).await
+                }
+                AccountCommand::Claim { output_id, category } => {
+                    claim_command(&account_handle, output_id, category, &transaction_flags).await
+                }
+                AccountCommand::Claimable { expiring_within } => {
+                    claimable_command(&account_handle, expiring_within).await
+                }
+                AccountCommand::Contacts(command) => contacts_command(&account_handle, command).await,
+                AccountCommand::Consolidate { options } => {
+                    consolidate_command(&account_handle, options, &transaction_flags).await
+                }
+                AccountCommand::CreateAliasOutput {
+                    state_controller,
+                    governor,
+                    state_metadata_hex,
+                    state_metadata_file,
+                    immutable_metadata_hex,
+                    immutable_metadata_file,
+                    issuer,
+                } => {
+                    create_alias_outputs_command(
+                        &account_handle,
+                        state_controller,
+                        governor,
+                        bytes_from_hex_or_file(state_metadata_hex, state_metadata_file).await?,
+                        bytes_from_hex_or_file(immutable_metadata_hex, immutable_metadata_file).await?,
+                        issuer,
+                        &transaction_flags,
+                    )
+                    .await
                 }
-                AccountCommand::BurnNft { nft_id } => burn_nft_command(&account_handle, nft_id).await,
-                AccountCommand::Claim { output_id } => claim_command(&account_handle, output_id).await,
-                AccountCommand::Consolidate => consolidate_command(&account_handle).await,
-                AccountCommand::CreateAliasOutput => create_alias_outputs_command(&account_handle).await,
                 AccountCommand::DecreaseNativeTokenSupply { token_id, amount } => {
-                    decrease_native_token_command(&account_handle, token_id, amount).await
+                    decrease_native_token_command(&account_handle, token_id, amount, &transaction_flags).await
+                }
+                AccountCommand::DestroyAlias { alias_id } => {
+                    destroy_alias_command(&account_handle, alias_id, &transaction_flags).await
                 }
-                AccountCommand::DestroyAlias { alias_id } => destroy_alias_command(&account_handle, alias_id).await,
                 AccountCommand::DestroyFoundry { foundry_id } => {
-                    destroy_foundry_command(&account_handle, foundry_id).await
+                    destroy_foundry_command(&account_handle, foundry_id, &transaction_flags).await
                 }
                 AccountCommand::Exit => {
                     return Ok(true);
                 }
+                AccountCommand::ExportTransactions { path, format, filters } => {
+                    export_transactions_command(&account_handle, path, format, filters).await
+                }
                 AccountCommand::Faucet { url, address } => faucet_command(&account_handle, url, address).await,
                 AccountCommand::IncreaseNativeTokenSupply { token_id, amount } => {
-                    increase_native_token_command(&account_handle, token_id, amount).await
+                    increase_native_token_command(&account_handle, token_id, amount, &transaction_flags).await
+                }
+                AccountCommand::LabelAddress { address, label } => {
+                    label_address_command(&account_handle, address, label).await
+                }
+                AccountCommand::LockOutput { output_id, note } => {
+                    lock_output_command(&account_handle, output_id, note).await
                 }
                 AccountCommand::MintNativeToken {
                     circulating_supply,
@@ -98,6 +193,7 @@ pub async fn account_prompt_internal(account_handle: AccountHandle) -> Result<bo
                         circulating_supply,
                         maximum_supply,
                         bytes_from_hex_or_file(foundry_metadata_hex, foundry_metadata_file).await?,
+                        &transaction_flags,
                     )
                     .await
                 }
@@ -119,37 +215,134 @@ pub async fn account_prompt_internal(account_handle: AccountHandle) -> Result<bo
                         tag,
                         sender,
                         issuer,
+                        &transaction_flags,
                     )
                     .await
                 }
                 AccountCommand::NewAddress => new_address_command(&account_handle).await,
+                AccountCommand::PrepareTransaction { .. } => Err(Error::Miscellaneous(
+                    "prepare-transaction can't prepare another prepare-transaction".to_string(),
+                )),
+                AccountCommand::NoteTransaction { transaction_id, note } => {
+                    note_transaction_command(&account_handle, transaction_id, note).await
+                }
+                AccountCommand::Pending => pending_command(&account_handle).await,
                 AccountCommand::Output { output_id } => output_command(&account_handle, output_id).await,
-                AccountCommand::Outputs => outputs_command(&account_handle).await,
-                AccountCommand::Send { address, amount } => send_command(&account_handle, address, amount).await,
+                AccountCommand::Outputs { filters } => outputs_command(&account_handle, filters).await,
+                AccountCommand::Reattach { block_id } => reattach_command(&account_handle, block_id).await,
+                AccountCommand::Reject { output_id, undo } => reject_command(&account_handle, output_id, undo).await,
+                AccountCommand::Retry { transaction_id } => retry_command(&account_handle, transaction_id).await,
+                AccountCommand::SendOutputs {
+                    path,
+                    remainder_address,
+                } => send_outputs_command(&account_handle, path, remainder_address, &transaction_flags).await,
+                AccountCommand::SignTransaction {
+                    prepared_path,
+                    signed_path,
+                } => sign_transaction_command(&account_handle, prepared_path, signed_path, &transaction_flags).await,
+                AccountCommand::Send { address, amount } => {
+                    send_command(&account_handle, address, amount, &transaction_flags).await
+                }
+                AccountCommand::SendBatch { path } => {
+                    send_batch_command(&account_handle, path, &transaction_flags).await
+                }
                 AccountCommand::SendMicro { address, amount } => {
-                    send_micro_command(&account_handle, address, amount).await
+                    send_micro_command(&account_handle, address, amount, &transaction_flags).await
                 }
                 AccountCommand::SendNativeToken {
                     address,
                     token_id,
                     amount,
                     gift_storage_deposit,
-                } => send_native_token_command(&account_handle, address, token_id, amount, gift_storage_deposit).await,
-                AccountCommand::SendNft { address, nft_id } => send_nft_command(&account_handle, address, nft_id).await,
-                AccountCommand::Sync => sync_command(&account_handle).await,
-                AccountCommand::Transactions => transactions_command(&account_handle).await,
-                AccountCommand::UnspentOutputs => unspent_outputs_command(&account_handle).await,
-                AccountCommand::Vote { event_id, answers } => vote_command(&account_handle, event_id, answers).await,
+                } => {
+                    send_native_token_command(
+                        &account_handle,
+                        address,
+                        token_id,
+                        amount,
+                        gift_storage_deposit,
+                        &transaction_flags,
+                    )
+                    .await
+                }
+                AccountCommand::SendNft { address, nft_id } => {
+                    send_nft_command(&account_handle, address, nft_id, &transaction_flags).await
+                }
+                AccountCommand::SendOutput {
+                    address,
+                    amount,
+                    tag,
+                    metadata_hex,
+                    metadata_file,
+                    sender,
+                    expiration,
+                    timelock,
+                    storage_deposit_return,
+                    return_address,
+                    tagged_data_tag,
+                    tagged_data_hex,
+                    tagged_data_file,
+                } => {
+                    send_output_command(
+                        &account_handle,
+                        address,
+                        amount,
+                        tag,
+                        bytes_from_hex_or_file(metadata_hex, metadata_file).await?,
+                        sender,
+                        expiration,
+                        timelock,
+                        storage_deposit_return,
+                        return_address,
+                        tagged_data_tag,
+                        bytes_from_hex_or_file(tagged_data_hex, tagged_data_file).await?,
+                        &transaction_flags,
+                    )
+                    .await
+                }
+                AccountCommand::Sweep {
+                    address,
+                    include_nfts,
+                    include_aliases,
+                } => {
+                    sweep_command(
+                        &account_handle,
+                        address,
+                        include_nfts,
+                        include_aliases,
+                        &transaction_flags,
+                    )
+                    .await
+                }
+                AccountCommand::StorageDeposit { output, owned } => {
+                    storage_deposit_command(&account_handle, output, owned).await
+                }
+                AccountCommand::SubmitTransaction { path } => {
+                    submit_transaction_command(&account_handle, path, &transaction_flags).await
+                }
+                AccountCommand::Sync => sync_command(&account_handle, &transaction_flags).await,
+                AccountCommand::Transaction { transaction_id } => {
+                    transaction_command(&account_handle, transaction_id).await
+                }
+                AccountCommand::Transactions { filters } => transactions_command(&account_handle, filters).await,
+                AccountCommand::UnlockOutput { output_id } => unlock_output_command(output_id).await,
+                AccountCommand::UnspentOutputs { filters } => unspent_outputs_command(&account_handle, filters).await,
+                AccountCommand::WaitTransaction { transaction_id } => {
+                    wait_transaction_command(&account_handle, transaction_id, &transaction_flags).await
+                }
+                AccountCommand::Vote { event_id, answers } => {
+                    vote_command(&account_handle, event_id, answers, &transaction_flags).await
+                }
                 AccountCommand::StopParticipating { event_id } => {
-                    stop_participating_command(&account_handle, event_id).await
+                    stop_participating_command(&account_handle, event_id, &transaction_flags).await
                 }
                 AccountCommand::ParticipationOverview => participation_overview_command(&account_handle).await,
                 AccountCommand::VotingPower => voting_power_command(&account_handle).await,
                 AccountCommand::IncreaseVotingPower { amount } => {
-                    increase_voting_power_command(&account_handle, amount).await
+                    increase_voting_power_command(&account_handle, amount, &transaction_flags).await
                 }
                 AccountCommand::DecreaseVotingPower { amount } => {
-                    decrease_voting_power_command(&account_handle, amount).await
+                    decrease_voting_power_command(&account_handle, amount, &transaction_flags).await
                 }
                 AccountCommand::VotingOutput => voting_output_command(&account_handle).await,
             } {
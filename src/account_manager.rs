@@ -1,20 +1,21 @@
 // Copyright 2020-2022 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use std::env::var_os;
-
 use iota_wallet::{
     account_manager::AccountManager,
     secret::{stronghold::StrongholdSecretManager, SecretManager},
 };
 
 use crate::{
-    command::account_manager::{
-        backup_command, change_password_command, init_command, mnemonic_command, new_command, restore_command,
-        set_node_command, sync_command, AccountManagerCli, AccountManagerCommand,
+    command::{
+        account_manager::{
+            backup_command, change_password_command, init_command, mnemonic_command, new_command, restore_command,
+            set_node_command, sync_command, AccountManagerCli, AccountManagerCommand,
+        },
+        watch_only::watch_only_command,
     },
     error::Error,
-    helper::get_password,
+    helper::{get_password, storage_path},
 };
 
 pub async fn new_account_manager(cli: AccountManagerCli) -> Result<(Option<AccountManager>, Option<String>), Error> {
@@ -22,11 +23,13 @@ pub async fn new_account_manager(cli: AccountManagerCli) -> Result<(Option<Accou
         mnemonic_command().await?;
         return Ok((None, None));
     }
+    // Watch-only accounts have no secret, Stronghold isn't unlocked for them.
+    if let Some(AccountManagerCommand::WatchOnly(command)) = cli.command {
+        watch_only_command(command).await?;
+        return Ok((None, None));
+    }
 
-    let storage_path = var_os("WALLET_DATABASE_PATH").map_or_else(
-        || "./stardust-cli-wallet-db".to_string(),
-        |os_str| os_str.into_string().expect("invalid WALLET_DATABASE_PATH"),
-    );
+    let storage_path = storage_path();
     let snapshot_path = std::path::Path::new("./stardust-cli-wallet.stronghold");
     let password = if let Some(AccountManagerCommand::Restore { .. }) = &cli.command {
         get_password("Stronghold password", false)?
@@ -67,7 +70,8 @@ pub async fn new_account_manager(cli: AccountManagerCli) -> Result<(Option<Accou
                 // PANIC: this will never happen because these variants have already been checked.
                 AccountManagerCommand::Init(_)
                 | AccountManagerCommand::Mnemonic
-                | AccountManagerCommand::Restore { .. } => unreachable!(),
+                | AccountManagerCommand::Restore { .. }
+                | AccountManagerCommand::WatchOnly(_) => unreachable!(),
             };
 
             (account_manager, account)
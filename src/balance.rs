@@ -0,0 +1,470 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+};
+
+use chrono::Utc;
+use iota_wallet::{
+    account::{AccountHandle, OutputsToClaim},
+    iota_client::{
+        block::{
+            address::Address,
+            output::{FoundryId, Output, Rent, TokenId},
+        },
+        Client,
+    },
+    U256,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::Error, helper::load_storage_file, labels::Labels, transaction::output_address, watch_only::WatchOnlyAccount,
+};
+
+/// Balance of the account, rendered by `balance`.
+#[derive(Debug, Serialize)]
+pub struct BalanceReport {
+    pub base_coin: BaseCoinReport,
+    pub required_storage_deposit: StorageDepositReport,
+    pub native_tokens: Vec<NativeTokenReport>,
+    pub nfts: Vec<String>,
+    pub aliases: Vec<String>,
+    pub foundries: Vec<String>,
+    /// Outputs with unlock conditions that may prevent spending them, and whether they can be unlocked now.
+    pub potentially_locked_outputs: BTreeMap<String, bool>,
+    /// Outputs with expiration or storage deposit return unlock conditions that can be claimed.
+    pub claimable_outputs: Vec<String>,
+    /// Base coins held by each labelled address.
+    pub labelled_addresses: Vec<LabelledAddressReport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BaseCoinReport {
+    pub ticker: String,
+    pub decimals: u32,
+    pub total: u64,
+    pub available: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StorageDepositReport {
+    pub total: u64,
+    pub basic: u64,
+    pub nft: u64,
+    pub alias: u64,
+    pub foundry: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NativeTokenReport {
+    pub token_id: String,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub decimals: Option<u32>,
+    pub total: String,
+    pub available: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LabelledAddressReport {
+    pub label: String,
+    pub address: String,
+    pub amount: u64,
+}
+
+/// Fields of the IRC30 metadata of a native token, stored as immutable metadata of its foundry.
+#[derive(Debug, Deserialize)]
+struct Irc30Metadata {
+    name: Option<String>,
+    symbol: Option<String>,
+    decimals: Option<u32>,
+}
+
+impl BalanceReport {
+    pub async fn new(account_handle: &AccountHandle) -> Result<Self, Error> {
+        let balance = account_handle.balance().await?;
+        let base_token = account_handle.client().get_info().await?.node_info.base_token;
+        let bech32_hrp = account_handle.client().get_bech32_hrp().await?;
+        let labels = load_storage_file::<Labels>().await?;
+        let required_storage_deposit = &balance.required_storage_deposit;
+        let mut native_tokens = Vec::new();
+
+        for native_token in &balance.native_tokens {
+            let metadata = token_metadata(account_handle.client(), &native_token.token_id).await;
+
+            native_tokens.push(NativeTokenReport {
+                token_id: native_token.token_id.to_string(),
+                name: metadata.as_ref().and_then(|metadata| metadata.name.clone()),
+                symbol: metadata.as_ref().and_then(|metadata| metadata.symbol.clone()),
+                decimals: metadata.as_ref().and_then(|metadata| metadata.decimals),
+                total: native_token.total.to_string(),
+                available: native_token.available.to_string(),
+            });
+        }
+
+        let mut labelled_amounts = BTreeMap::<String, u64>::new();
+
+        for output_data in account_handle.unspent_outputs(None).await? {
+            let address = output_data.address.to_bech32(&bech32_hrp);
+
+            if labels.addresses.contains_key(&address) {
+                *labelled_amounts.entry(address).or_default() += output_data.output.amount();
+            }
+        }
+
+        Ok(Self {
+            base_coin: BaseCoinReport {
+                ticker: base_token.ticker_symbol,
+                decimals: base_token.decimals,
+                total: balance.base_coin.total,
+                available: balance.base_coin.available,
+            },
+            required_storage_deposit: StorageDepositReport {
+                total: required_storage_deposit.basic
+                    + required_storage_deposit.nft
+                    + required_storage_deposit.alias
+                    + required_storage_deposit.foundry,
+                basic: required_storage_deposit.basic,
+                nft: required_storage_deposit.nft,
+                alias: required_storage_deposit.alias,
+                foundry: required_storage_deposit.foundry,
+            },
+            native_tokens,
+            nfts: balance.nfts.iter().map(ToString::to_string).collect(),
+            aliases: balance.aliases.iter().map(ToString::to_string).collect(),
+            foundries: balance.foundries.iter().map(ToString::to_string).collect(),
+            potentially_locked_outputs: balance
+                .potentially_locked_outputs
+                .iter()
+                .map(|(output_id, unlockable)| (output_id.to_string(), *unlockable))
+                .collect(),
+            claimable_outputs: account_handle
+                .get_unlockable_outputs_with_additional_unlock_conditions(OutputsToClaim::All)
+                .await?
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            labelled_addresses: labelled_amounts
+                .into_iter()
+                .map(|(address, amount)| LabelledAddressReport {
+                    label: labels.addresses[&address].clone(),
+                    address,
+                    amount,
+                })
+                .collect(),
+        })
+    }
+
+    /// Computes the balance of a watch-only account from its outputs as of the last sync.
+    ///
+    /// Outputs with a timelock, an expiration or a storage deposit return aren't available and, without keys, nothing
+    /// can be claimed. Like in the wallet, an output whose expiration has passed belongs to its return address and is
+    /// only counted if that address is watched.
+    pub async fn watch_only(account: &WatchOnlyAccount) -> Result<Self, Error> {
+        let client = Client::builder().with_node(&account.node_url)?.finish()?;
+        let base_token = client.get_info().await?.node_info.base_token;
+        let rent_structure = client.get_rent_structure().await?;
+        let labels = load_storage_file::<Labels>().await?;
+        let mut watched_addresses = HashSet::new();
+        let mut bech32_hrp = String::new();
+
+        for address in &account.addresses {
+            let (hrp, address) = Address::try_from_bech32(address)?;
+            watched_addresses.insert(address);
+            bech32_hrp = hrp;
+        }
+
+        let now = Utc::now().timestamp() as u32;
+        let mut base_coin = BaseCoinReport {
+            ticker: base_token.ticker_symbol,
+            decimals: base_token.decimals,
+            total: 0,
+            available: 0,
+        };
+        let mut storage_deposit = StorageDepositReport {
+            total: 0,
+            basic: 0,
+            nft: 0,
+            alias: 0,
+            foundry: 0,
+        };
+        // Total and available amounts of each native token.
+        let mut native_token_amounts = BTreeMap::<TokenId, (U256, U256)>::new();
+        let mut nfts = Vec::new();
+        let mut aliases = Vec::new();
+        let mut potentially_locked_outputs = BTreeMap::new();
+        let mut labelled_amounts = BTreeMap::<String, u64>::new();
+
+        for (output_id, output) in account.unspent_outputs()? {
+            let expired_return_address = output
+                .unlock_conditions()
+                .and_then(|unlock_conditions| unlock_conditions.expiration())
+                .filter(|expiration| expiration.timestamp() <= now)
+                .map(|expiration| *expiration.return_address());
+            let address = expired_return_address.or_else(|| output_address(&output));
+
+            if !address.map_or(false, |address| watched_addresses.contains(&address)) {
+                continue;
+            }
+
+            let rent_cost = output.rent_cost(&rent_structure);
+            storage_deposit.total += rent_cost;
+
+            match &output {
+                Output::Basic(_) => storage_deposit.basic += rent_cost,
+                Output::Nft(nft_output) => {
+                    storage_deposit.nft += rent_cost;
+                    nfts.push(nft_output.nft_id_non_null(&output_id).to_string());
+                }
+                Output::Alias(alias_output) => {
+                    storage_deposit.alias += rent_cost;
+                    aliases.push(alias_output.alias_id_non_null(&output_id).to_string());
+                }
+                Output::Foundry(_) => storage_deposit.foundry += rent_cost,
+                Output::Treasury(_) => {}
+            }
+
+            let available = match output.unlock_conditions() {
+                // Only a timelock can still hold back an expired output.
+                Some(unlock_conditions) if expired_return_address.is_some() => unlock_conditions
+                    .timelock()
+                    .map_or(true, |timelock| timelock.timestamp() <= now),
+                Some(unlock_conditions)
+                    if unlock_conditions.timelock().is_some()
+                        || unlock_conditions.expiration().is_some()
+                        || unlock_conditions.storage_deposit_return().is_some() =>
+                {
+                    let unlockable = unlock_conditions
+                        .timelock()
+                        .map_or(true, |timelock| timelock.timestamp() <= now)
+                        && unlock_conditions
+                            .expiration()
+                            .map_or(true, |expiration| now < expiration.timestamp())
+                        && unlock_conditions.storage_deposit_return().is_none();
+                    potentially_locked_outputs.insert(output_id.to_string(), unlockable);
+                    false
+                }
+                _ => true,
+            };
+
+            base_coin.total += output.amount();
+            if available {
+                base_coin.available += output.amount();
+            }
+            if let Some(native_tokens) = output.native_tokens() {
+                for native_token in native_tokens.iter() {
+                    let amounts = native_token_amounts.entry(*native_token.token_id()).or_default();
+                    amounts.0 += *native_token.amount();
+                    if available {
+                        amounts.1 += *native_token.amount();
+                    }
+                }
+            }
+            if let Some(address) = address {
+                let address = address.to_bech32(&bech32_hrp);
+
+                if labels.addresses.contains_key(&address) {
+                    *labelled_amounts.entry(address).or_default() += output.amount();
+                }
+            }
+        }
+
+        let mut native_tokens = Vec::new();
+
+        for (token_id, (total, available)) in native_token_amounts {
+            let metadata = token_metadata(&client, &token_id).await;
+
+            native_tokens.push(NativeTokenReport {
+                token_id: token_id.to_string(),
+                name: metadata.as_ref().and_then(|metadata| metadata.name.clone()),
+                symbol: metadata.as_ref().and_then(|metadata| metadata.symbol.clone()),
+                decimals: metadata.as_ref().and_then(|metadata| metadata.decimals),
+                total: total.to_string(),
+                available: available.to_string(),
+            });
+        }
+
+        Ok(Self {
+            base_coin,
+            required_storage_deposit: storage_deposit,
+            native_tokens,
+            nfts,
+            aliases,
+            foundries: Vec::new(),
+            potentially_locked_outputs,
+            claimable_outputs: Vec::new(),
+            labelled_addresses: labelled_amounts
+                .into_iter()
+                .map(|(address, amount)| LabelledAddressReport {
+                    label: labels.addresses[&address].clone(),
+                    address,
+                    amount,
+                })
+                .collect(),
+        })
+    }
+}
+
+impl fmt::Display for BalanceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let base_coin = &self.base_coin;
+        let storage_deposit = &self.required_storage_deposit;
+
+        writeln!(
+            f,
+            "Base coin: {} {} total, {} {} available",
+            format_amount(U256::from(base_coin.total), base_coin.decimals),
+            base_coin.ticker,
+            format_amount(U256::from(base_coin.available), base_coin.decimals),
+            base_coin.ticker
+        )?;
+        write!(
+            f,
+            "Required storage deposit: {} (basic {}, NFT {}, alias {}, foundry {})",
+            storage_deposit.total,
+            storage_deposit.basic,
+            storage_deposit.nft,
+            storage_deposit.alias,
+            storage_deposit.foundry
+        )?;
+
+        if !self.native_tokens.is_empty() {
+            write!(f, "\nNative tokens:")?;
+        }
+        for native_token in &self.native_tokens {
+            let decimals = native_token.decimals.unwrap_or(0);
+            let amount = |amount: &str| {
+                U256::from_dec_str(amount).map_or_else(|_| amount.to_string(), |amount| format_amount(amount, decimals))
+            };
+            let name = match (&native_token.name, &native_token.symbol) {
+                (Some(name), Some(symbol)) => format!(" ({name}, {symbol})"),
+                (Some(name), None) | (None, Some(name)) => format!(" ({name})"),
+                (None, None) => String::new(),
+            };
+
+            write!(
+                f,
+                "\n  {}{name}: {} total, {} available",
+                native_token.token_id,
+                amount(&native_token.total),
+                amount(&native_token.available)
+            )?;
+        }
+
+        write_list(f, "NFTs", &self.nfts)?;
+        write_list(f, "Aliases", &self.aliases)?;
+        write_list(f, "Foundries", &self.foundries)?;
+
+        if !self.potentially_locked_outputs.is_empty() {
+            write!(f, "\nPotentially locked outputs:")?;
+        }
+        for (output_id, unlockable) in &self.potentially_locked_outputs {
+            write!(
+                f,
+                "\n  {output_id}: {}",
+                if *unlockable { "unlockable now" } else { "locked" }
+            )?;
+        }
+
+        write_list(f, "Outputs awaiting claim", &self.claimable_outputs)?;
+
+        if !self.labelled_addresses.is_empty() {
+            write!(f, "\nLabelled addresses:")?;
+        }
+        for labelled_address in &self.labelled_addresses {
+            write!(
+                f,
+                "\n  {} ({}): {}",
+                labelled_address.label, labelled_address.address, labelled_address.amount
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_list(f: &mut fmt::Formatter<'_>, title: &str, items: &[String]) -> fmt::Result {
+    if !items.is_empty() {
+        write!(f, "\n{title}:")?;
+    }
+    for item in items {
+        write!(f, "\n  {item}")?;
+    }
+
+    Ok(())
+}
+
+/// Formats an amount of the smallest unit in the unit having `decimals` decimals.
+///
+/// Native token decimals come from foundry metadata anyone can set, the raw amount is kept when they don't fit in a
+/// `U256`.
+pub fn format_amount(amount: U256, decimals: u32) -> String {
+    let Some(divisor) = U256::from(10).checked_pow(U256::from(decimals)) else {
+        return amount.to_string();
+    };
+    // The fraction is a string, zero padding only applies to numbers.
+    let fraction = format!("{:0>width$}", (amount % divisor).to_string(), width = decimals as usize);
+    let fraction = fraction.trim_end_matches('0');
+
+    if fraction.is_empty() {
+        (amount / divisor).to_string()
+    } else {
+        format!("{}.{fraction}", amount / divisor)
+    }
+}
+
+/// Decimals of a native token, from the IRC30 metadata of its foundry.
+pub async fn token_decimals(client: &Client, token_id: &TokenId) -> Option<u32> {
+    token_metadata(client, token_id).await?.decimals
+}
+
+/// Fetches the IRC30 metadata of a native token from its foundry, if it has any.
+async fn token_metadata(client: &Client, token_id: &TokenId) -> Option<Irc30Metadata> {
+    let output_id = client.foundry_output_id(FoundryId::from(*token_id)).await.ok()?;
+    let output = Output::try_from_dto_unverified(&client.get_output(&output_id).await.ok()?.output).ok()?;
+
+    if let Output::Foundry(foundry_output) = output {
+        serde_json::from_slice(foundry_output.immutable_features().metadata()?.data()).ok()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_amount_decimals() {
+        assert_eq!(format_amount(U256::from(1_500_000u64), 6), "1.5");
+        assert_eq!(format_amount(U256::from(1_000_000u64), 6), "1");
+        assert_eq!(format_amount(U256::from(1u64), 6), "0.000001");
+        assert_eq!(format_amount(U256::from(120u64), 2), "1.2");
+        assert_eq!(format_amount(U256::zero(), 6), "0");
+    }
+
+    #[test]
+    fn format_amount_without_decimals() {
+        assert_eq!(format_amount(U256::from(42u64), 0), "42");
+        assert_eq!(format_amount(U256::zero(), 0), "0");
+    }
+
+    #[test]
+    fn format_amount_large() {
+        assert_eq!(format_amount(U256::MAX, 0), U256::MAX.to_string());
+        assert_eq!(
+            format_amount(U256::from_dec_str("123456789012345678901234567890").unwrap(), 18),
+            "123456789012.34567890123456789"
+        );
+    }
+
+    #[test]
+    fn format_amount_absurd_decimals() {
+        assert_eq!(format_amount(U256::from(1_500_000u64), 78), "1500000");
+        assert_eq!(format_amount(U256::from(1_500_000u64), u32::MAX), "1500000");
+        assert_eq!(format_amount(U256::from(15u64), 77), format!("0.{}15", "0".repeat(75)));
+    }
+}
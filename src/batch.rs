@@ -0,0 +1,247 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{collections::HashMap, path::Path};
+
+use iota_wallet::{
+    account::types::InclusionState,
+    iota_client::crypto::hashes::{blake2b::Blake2b256, Digest},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::Error,
+    helper::{read_json_file_or_default, write_json_file},
+};
+
+/// A recipient of a `send-batch` file.
+///
+/// A row either sends base coins, optionally with a native token, or an NFT.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BatchRow {
+    pub address: String,
+    #[serde(default)]
+    pub amount: Option<u64>,
+    #[serde(default)]
+    pub token_id: Option<String>,
+    #[serde(default)]
+    pub token_amount: Option<String>,
+    #[serde(default)]
+    pub nft_id: Option<String>,
+}
+
+impl BatchRow {
+    /// Hex encoded hash of the recipient and of what is sent to it.
+    pub fn hash(&self) -> String {
+        let content = format!(
+            "{}|{}|{}|{}|{}",
+            self.address,
+            self.amount.map(|amount| amount.to_string()).unwrap_or_default(),
+            self.token_id.as_deref().unwrap_or_default(),
+            self.token_amount.as_deref().unwrap_or_default(),
+            self.nft_id.as_deref().unwrap_or_default(),
+        );
+
+        prefix_hex::encode(Blake2b256::digest(content.as_bytes()).as_slice())
+    }
+}
+
+/// Reads the rows of a batch file, JSON if the extension is `.json`, CSV with a header line otherwise.
+pub async fn read_batch_file(path: &str) -> Result<Vec<BatchRow>, Error> {
+    let content = tokio::fs::read_to_string(path).await?;
+
+    if Path::new(path)
+        .extension()
+        .map_or(false, |extension| extension == "json")
+    {
+        Ok(serde_json::from_str(&content)?)
+    } else {
+        parse_csv(&content)
+    }
+}
+
+fn parse_csv(content: &str) -> Result<Vec<BatchRow>, Error> {
+    // Line numbers start at 1 and count the blank lines, like in an editor.
+    let mut lines = content
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty());
+    let header: Vec<&str> = match lines.next() {
+        Some((_, header)) => header.split(',').map(str::trim).collect(),
+        None => return Ok(Vec::new()),
+    };
+
+    if !header.contains(&"address") {
+        return Err(Error::Miscellaneous(
+            "batch file header has no \"address\" column".to_string(),
+        ));
+    }
+
+    lines
+        .map(|(line_number, line)| {
+            let values = line.split(',').map(str::trim).collect::<Vec<_>>();
+            let mut row = BatchRow::default();
+
+            if values.len() != header.len() {
+                return Err(Error::Miscellaneous(format!(
+                    "line {line_number} has {} field(s), the header has {}",
+                    values.len(),
+                    header.len()
+                )));
+            }
+
+            for (column, value) in header.iter().zip(values) {
+                let value = (!value.is_empty()).then(|| value.to_string());
+
+                match *column {
+                    "address" => row.address = value.unwrap_or_default(),
+                    "amount" => {
+                        row.amount = value
+                            .map(|amount| amount.parse())
+                            .transpose()
+                            .map_err(|e: std::num::ParseIntError| Error::Miscellaneous(e.to_string()))?
+                    }
+                    "token_id" => row.token_id = value,
+                    "token_amount" => row.token_amount = value,
+                    "nft_id" => row.nft_id = value,
+                    column => return Err(Error::Miscellaneous(format!("unknown batch file column \"{column}\""))),
+                }
+            }
+
+            Ok(row)
+        })
+        .collect()
+}
+
+/// Outcome of a single row, stored in the report file next to the batch file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchRowReport {
+    /// Row number at the time of the run, starting at 1.
+    pub row: usize,
+    /// Hash of the row content, so that rows are matched by content and not by position when resuming.
+    pub row_hash: String,
+    pub address: String,
+    pub transaction_id: Option<String>,
+    pub block_id: Option<String>,
+    /// Last known inclusion state of the transaction.
+    #[serde(default)]
+    pub inclusion_state: Option<InclusionState>,
+    pub error: Option<String>,
+}
+
+impl BatchRowReport {
+    /// Whether the row was sent in a transaction that isn't known to be conflicting.
+    pub fn is_sent(&self) -> bool {
+        self.transaction_id.is_some()
+            && !matches!(
+                self.inclusion_state,
+                Some(InclusionState::Conflicting | InclusionState::UnknownPruned)
+            )
+    }
+}
+
+/// Report of all `send-batch` runs of a batch file, in the order the rows were processed.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BatchReport {
+    pub rows: Vec<BatchRowReport>,
+}
+
+impl BatchReport {
+    pub fn path(batch_path: &str) -> String {
+        format!("{batch_path}.report.json")
+    }
+
+    /// Loads the report of a previous run, if any, so that already sent rows are skipped.
+    pub async fn load(batch_path: &str) -> Result<Self, Error> {
+        read_json_file_or_default(Self::path(batch_path)).await
+    }
+
+    pub async fn save(&self, batch_path: &str) -> Result<(), Error> {
+        write_json_file(Self::path(batch_path), self).await
+    }
+
+    /// Number of sent rows per row hash, a row listed twice in the batch file has to be sent twice.
+    pub fn sent_rows(&self) -> HashMap<String, usize> {
+        let mut sent_rows = HashMap::new();
+
+        for report in self.rows.iter().filter(|report| report.is_sent()) {
+            *sent_rows.entry(report.row_hash.clone()).or_default() += 1;
+        }
+
+        sent_rows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_csv_rows() {
+        let rows = parse_csv(
+            "address, amount, token_id, token_amount, nft_id\n\
+             rms1qa, 1000000, , ,\n\
+             \n\
+             rms1qb, , 0x08aa, 10,\n\
+             rms1qc, , , , 0xbb\n",
+        )
+        .unwrap();
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].address, "rms1qa");
+        assert_eq!(rows[0].amount, Some(1_000_000));
+        assert_eq!(rows[0].token_id, None);
+        assert_eq!(rows[1].amount, None);
+        assert_eq!(rows[1].token_id.as_deref(), Some("0x08aa"));
+        assert_eq!(rows[1].token_amount.as_deref(), Some("10"));
+        assert_eq!(rows[2].nft_id.as_deref(), Some("0xbb"));
+    }
+
+    #[test]
+    fn parse_csv_column_order() {
+        let rows = parse_csv("amount,address\n5,rms1qa\n").unwrap();
+
+        assert_eq!(rows[0].address, "rms1qa");
+        assert_eq!(rows[0].amount, Some(5));
+    }
+
+    #[test]
+    fn parse_csv_empty() {
+        assert!(parse_csv("").unwrap().is_empty());
+        assert!(parse_csv("address,amount\n").unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_csv_errors() {
+        assert!(parse_csv("amount\n5\n").is_err());
+        assert!(parse_csv("address,memo\nrms1qa,hello\n").is_err());
+        assert!(parse_csv("address,amount\nrms1qa,-5\n").is_err());
+        assert!(parse_csv("address,amount\nrms1qa,1.5\n").is_err());
+    }
+
+    #[test]
+    fn parse_csv_field_count() {
+        let error = parse_csv("address,amount\nrms1qa,5\n\nrms1qb,5,extra\n").unwrap_err();
+        assert_eq!(error.to_string(), "line 4 has 3 field(s), the header has 2");
+
+        let error = parse_csv("address,amount\nrms1qa\n").unwrap_err();
+        assert_eq!(error.to_string(), "line 2 has 1 field(s), the header has 2");
+    }
+
+    #[test]
+    fn row_hash() {
+        let row = BatchRow {
+            address: "rms1qa".to_string(),
+            amount: Some(5),
+            ..Default::default()
+        };
+        let other_amount = BatchRow {
+            amount: Some(6),
+            ..row.clone()
+        };
+
+        assert_eq!(row.hash(), row.clone().hash());
+        assert_ne!(row.hash(), other_amount.hash());
+    }
+}
@@ -0,0 +1,128 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::Utc;
+use iota_wallet::{account::AccountHandle, iota_client::block::output::OutputId};
+use serde::{Deserialize, Serialize};
+
+use crate::{error::Error, helper::StorageFile};
+
+/// Claiming preferences.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ClaimSettings {
+    /// Ids of the incoming outputs that are never claimed, so that they return to their sender once expired.
+    #[serde(default)]
+    pub rejected_outputs: BTreeSet<String>,
+    /// Windows in seconds, indexed by account index: `sync` claims the outputs of the account whose expiration
+    /// deadline is within its window.
+    #[serde(default)]
+    pub auto_claim_windows: BTreeMap<u32, u64>,
+}
+
+impl StorageFile for ClaimSettings {
+    const FILE_NAME: &'static str = "claims.json";
+}
+
+/// Parses a duration such as `90s`, `30m`, `24h` or `7d` into seconds, a bare number being seconds.
+pub fn parse_duration(duration: &str) -> Result<u64, Error> {
+    let invalid = || Error::Miscellaneous(format!("invalid duration \"{duration}\", expected e.g. 30m, 24h or 7d"));
+    let (value, unit) = match duration.find(|c: char| !c.is_ascii_digit()) {
+        Some(index) => duration.split_at(index),
+        None => (duration, "s"),
+    };
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return Err(invalid()),
+    };
+
+    value
+        .parse::<u64>()
+        .ok()
+        .and_then(|value| value.checked_mul(multiplier))
+        .ok_or_else(invalid)
+}
+
+/// Formats seconds as the largest whole unit of `parse_duration`, e.g. `24h` for `86400`.
+pub fn format_duration(seconds: u64) -> String {
+    match seconds {
+        s if s != 0 && s % (24 * 60 * 60) == 0 => format!("{}d", s / (24 * 60 * 60)),
+        s if s != 0 && s % (60 * 60) == 0 => format!("{}h", s / (60 * 60)),
+        s if s != 0 && s % 60 == 0 => format!("{}m", s / 60),
+        s => format!("{s}s"),
+    }
+}
+
+/// Keeps the outputs of the account whose expiration deadline hasn't passed yet but is at most `window` seconds away.
+pub async fn expiring_outputs(account_handle: &AccountHandle, output_ids: Vec<OutputId>, window: u64) -> Vec<OutputId> {
+    let now = Utc::now().timestamp() as u64;
+    let mut expiring_outputs = Vec::new();
+
+    for output_id in output_ids {
+        let Some(output_data) = account_handle.get_output(&output_id).await else {
+            continue;
+        };
+        let expiration = output_data
+            .output
+            .unlock_conditions()
+            .and_then(|unlock_conditions| unlock_conditions.expiration())
+            .map(|expiration| expiration.timestamp() as u64);
+
+        if matches!(expiration, Some(expiration) if expiration > now && expiration - now <= window) {
+            expiring_outputs.push(output_id);
+        }
+    }
+
+    expiring_outputs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_duration_units() {
+        assert_eq!(parse_duration("90").unwrap(), 90);
+        assert_eq!(parse_duration("90s").unwrap(), 90);
+        assert_eq!(parse_duration("30m").unwrap(), 30 * 60);
+        assert_eq!(parse_duration("24h").unwrap(), 24 * 60 * 60);
+        assert_eq!(parse_duration("7d").unwrap(), 7 * 24 * 60 * 60);
+        assert_eq!(parse_duration("0h").unwrap(), 0);
+    }
+
+    #[test]
+    fn parse_duration_invalid() {
+        for duration in ["", "h", "-5m", "1.5h", "5 m", "5w", "5hh", "m5", " 5m"] {
+            assert!(parse_duration(duration).is_err(), "{duration:?} should be invalid");
+        }
+    }
+
+    #[test]
+    fn parse_duration_overflow() {
+        assert!(parse_duration(&format!("{}d", u64::MAX)).is_err());
+        assert!(parse_duration("99999999999999999999").is_err());
+        assert_eq!(parse_duration(&u64::MAX.to_string()).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn format_duration_units() {
+        assert_eq!(format_duration(0), "0s");
+        assert_eq!(format_duration(45), "45s");
+        assert_eq!(format_duration(90), "90s");
+        assert_eq!(format_duration(120), "2m");
+        assert_eq!(format_duration(3 * 60 * 60), "3h");
+        assert_eq!(format_duration(36 * 60 * 60), "36h");
+        assert_eq!(format_duration(7 * 24 * 60 * 60), "7d");
+    }
+
+    #[test]
+    fn format_duration_round_trip() {
+        for seconds in [1, 59, 60, 3600, 86400, 90061] {
+            assert_eq!(parse_duration(&format_duration(seconds)).unwrap(), seconds);
+        }
+    }
+}
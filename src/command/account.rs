@@ -1,30 +1,63 @@
 // Copyright 2020-2022 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use std::str::FromStr;
+use std::{
+    collections::{BTreeMap, HashSet, VecDeque},
+    str::FromStr,
+};
 
-use clap::{Parser, Subcommand};
+use chrono::Utc;
+use clap::{ArgGroup, Args, Parser, Subcommand};
 use iota_wallet::{
     account::{
-        types::{AccountAddress, TransactionDto},
-        AccountHandle, OutputsToClaim,
+        types::{AccountAddress, InclusionState, OutputData, Transaction, TransactionDto},
+        AccountHandle, OutputsToClaim, RemainderValueStrategy, TransactionOptions,
     },
+    error::Error as WalletError,
     iota_client::{
+        api::{PreparedTransactionData, PreparedTransactionDataDto, SignedTransactionData, SignedTransactionDataDto},
         api_types::plugins::participation::types::ParticipationEventId,
         block::{
-            address::Address,
+            address::{Address, AliasAddress},
+            input::INPUT_COUNT_MAX,
             output::{
-                unlock_condition::AddressUnlockCondition, AliasId, BasicOutputBuilder, FoundryId, NativeToken, NftId,
-                OutputId, TokenId, UnlockCondition,
+                dto::OutputDto,
+                feature::{IssuerFeature, MetadataFeature, SenderFeature, TagFeature},
+                unlock_condition::{
+                    AddressUnlockCondition, ExpirationUnlockCondition, GovernorAddressUnlockCondition,
+                    ImmutableAliasAddressUnlockCondition, StateControllerAddressUnlockCondition,
+                    StorageDepositReturnUnlockCondition, TimelockUnlockCondition,
+                },
+                AliasId, AliasOutput, AliasOutputBuilder, BasicOutputBuilder, Feature, FoundryId, FoundryOutputBuilder,
+                NativeToken, NativeTokens, NftId, NftOutputBuilder, Output, OutputId, Rent, RentStructure,
+                SimpleTokenScheme, TokenId, TokenScheme, UnlockCondition, OUTPUT_COUNT_MAX,
             },
+            payload::{transaction::TransactionId, TaggedDataPayload},
+            BlockId,
         },
+        error::Error as ClientError,
         request_funds_from_faucet,
     },
     AddressAndNftId, AddressNativeTokens, AddressWithAmount, AddressWithMicroAmount, NativeTokenOptions, NftOptions,
     U256,
 };
 
-use crate::error::Error;
+use crate::{
+    balance::{token_decimals, BalanceReport},
+    batch::{read_batch_file, BatchReport, BatchRow, BatchRowReport},
+    claims::{expiring_outputs, format_duration, parse_duration, ClaimSettings},
+    contacts::{address_argument, AddressBook, Contact},
+    error::Error,
+    helper::{bytes_from_hex_or_file, load_storage_file, read_json_file, save_storage_file, write_json_file},
+    history::{events_to_csv, parse_date, TransactionSummary},
+    labels::{set_or_remove, Labels},
+    locks::LockedOutputs,
+    output::{describe_claimable_output, describe_output, outputs_table, OutputContext},
+    transaction::{
+        account_addresses, confirm_transaction, output_address, output_kind, release_inputs, set_input_chains,
+        sign_and_submit_transaction, wait_for_transaction,
+    },
+};
 
 #[derive(Debug, Parser)]
 #[clap(version, long_about = None)]
@@ -32,24 +65,158 @@ use crate::error::Error;
 pub struct AccountCli {
     #[clap(subcommand)]
     pub command: AccountCommand,
+    #[clap(flatten)]
+    pub transaction_flags: TransactionFlags,
+}
+
+/// Flags of the commands issuing a transaction.
+#[derive(Debug, Clone, Default, Args)]
+pub struct TransactionFlags {
+    /// Sign and submit the transaction without asking to confirm its preview.
+    #[clap(short, long, global = true)]
+    pub yes: bool,
+    /// Prepare the transaction and print it as JSON, without signing nor submitting it.
+    #[clap(long, global = true)]
+    pub dry_run: bool,
+    /// Write the prepared transaction to this file instead of signing it, set by `prepare-transaction`.
+    #[clap(skip)]
+    pub prepared_transaction_path: Option<String>,
+    /// Wait until the transaction is included or conflicting once submitted.
+    #[clap(long, global = true)]
+    pub wait: bool,
+    /// Seconds to wait for the transaction, with `--wait` or `wait-transaction`.
+    #[clap(long, global = true, default_value_t = 120)]
+    pub wait_timeout: u64,
+    /// Comma separated ids of the outputs to use as the only inputs, instead of selecting them automatically.
+    #[clap(long, global = true, value_delimiter = ',')]
+    pub inputs: Vec<String>,
+    /// Comma separated ids of outputs that must be inputs, the others being selected automatically.
+    #[clap(long, global = true, value_delimiter = ',', conflicts_with = "inputs")]
+    pub mandatory_inputs: Vec<String>,
+}
+
+impl TransactionFlags {
+    /// Transaction options selecting the inputs given with `--inputs` or `--mandatory-inputs`.
+    pub fn transaction_options(&self) -> Result<TransactionOptions, Error> {
+        Ok(TransactionOptions {
+            custom_inputs: parse_output_ids(&self.inputs)?,
+            mandatory_inputs: parse_output_ids(&self.mandatory_inputs)?,
+            ..Default::default()
+        })
+    }
+
+    /// Outputs explicitly chosen as inputs with `--inputs` or `--mandatory-inputs`.
+    pub fn selected_inputs(&self) -> Result<Vec<OutputId>, Error> {
+        Ok(parse_output_ids(&self.inputs)?
+            .into_iter()
+            .chain(parse_output_ids(&self.mandatory_inputs)?)
+            .flatten()
+            .collect())
+    }
+}
+
+fn parse_output_ids(output_ids: &[String]) -> Result<Option<Vec<OutputId>>, Error> {
+    if output_ids.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(
+        output_ids
+            .iter()
+            .map(|output_id| OutputId::from_str(output_id))
+            .collect::<Result<Vec<_>, _>>()?,
+    ))
 }
 
 #[derive(Debug, Subcommand)]
 pub enum AccountCommand {
     /// List the account addresses.
     Addresses,
-    /// Print the account balance.
-    Balance,
+    /// Transfer the governorship of an alias to a bech32 encoded address: `alias-set-governor 0x...
+    /// rms1qztwng6cty8cfm42nzvq099ev7udhrnk0rw8jt8vttf9kpqnxhpsx869vr3`
+    AliasSetGovernor {
+        alias_id: String,
+        #[clap(value_parser = address_argument)]
+        address: String,
+    },
+    /// Hand the state controller of an alias to a bech32 encoded address: `alias-set-state-controller 0x...
+    /// rms1qztwng6cty8cfm42nzvq099ev7udhrnk0rw8jt8vttf9kpqnxhpsx869vr3`
+    AliasSetStateController {
+        alias_id: String,
+        #[clap(value_parser = address_argument)]
+        address: String,
+    },
+    /// Update the state metadata of an alias, or clear it with `--clear`: `alias-update-state 0x...
+    /// --state-metadata-hex 0x...`
+    AliasUpdateState {
+        alias_id: String,
+        #[clap(long, group = "state_metadata")]
+        state_metadata_hex: Option<String>,
+        #[clap(long, group = "state_metadata")]
+        state_metadata_file: Option<String>,
+        /// Clear the state metadata.
+        #[clap(long, group = "state_metadata")]
+        clear: bool,
+    },
+    /// Set the window before the expiration deadline of incoming outputs in which `sync` claims them, show it without
+    /// argument or disable it with `--off`: `auto-claim 24h`
+    AutoClaim {
+        window: Option<String>,
+        #[clap(long, conflicts_with = "window")]
+        off: bool,
+    },
+    /// Print the account balance: base coins, storage deposits, native tokens, NFTs, aliases, foundries, locked and
+    /// claimable outputs.
+    Balance {
+        /// Print the balance as JSON.
+        #[clap(long)]
+        json: bool,
+    },
     /// Burn a native token: `burn-native-token 0x... 100`
     BurnNativeToken { token_id: String, amount: String },
     /// Burn an NFT: `burn-nft 0x...`
     BurnNft { nft_id: String },
-    /// Claim outputs with storage deposit return, expiration or timelock unlock conditions.
-    Claim { output_id: Option<String> },
-    /// Consolidate all basic outputs into one address.
-    Consolidate,
-    /// Create a new alias output.
-    CreateAliasOutput,
+    /// Claim outputs with storage deposit return, expiration or timelock unlock conditions, all of them except the
+    /// rejected ones, or only a category: `claim --nfts`
+    Claim {
+        output_id: Option<String>,
+        #[clap(flatten)]
+        category: ClaimCategory,
+    },
+    /// List the outputs that can be claimed, with their sender, amount, return deposit and expiration deadline, only
+    /// the ones expiring soon with `--expiring-within 24h`.
+    Claimable {
+        #[clap(long)]
+        expiring_within: Option<String>,
+    },
+    /// Consolidate the basic outputs into one address, previewing the transactions with `--plan`: `consolidate
+    /// --threshold 10 --max-inputs 50 --plan`
+    Consolidate {
+        #[clap(flatten)]
+        options: ConsolidationOptions,
+    },
+    /// Manage the address book, contacts can be used as `@name` in place of any address.
+    #[clap(subcommand)]
+    Contacts(ContactsCommand),
+    /// Create a new alias output, controlled by the first address of the account unless other addresses are given:
+    /// `create-alias-output --state-controller rms1qztwng6cty8cfm42nzvq099ev7udhrnk0rw8jt8vttf9kpqnxhpsx869vr3
+    /// --state-metadata-hex 0x...`
+    CreateAliasOutput {
+        #[clap(long)]
+        state_controller: Option<String>,
+        #[clap(long)]
+        governor: Option<String>,
+        #[clap(long, group = "state_metadata")]
+        state_metadata_hex: Option<String>,
+        #[clap(long, group = "state_metadata")]
+        state_metadata_file: Option<String>,
+        #[clap(long, group = "immutable_metadata")]
+        immutable_metadata_hex: Option<String>,
+        #[clap(long, group = "immutable_metadata")]
+        immutable_metadata_file: Option<String>,
+        #[clap(long)]
+        issuer: Option<String>,
+    },
     /// Melt a native token: `decrease-native-token-supply 0x... 100`
     DecreaseNativeTokenSupply { token_id: String, amount: String },
     /// Destroy an alias: `destroy-alias 0x...`
@@ -58,13 +225,41 @@ pub enum AccountCommand {
     DestroyFoundry { foundry_id: String },
     /// Exit from the account prompt.
     Exit,
+    /// Export the balance-changing events of the transactions, for bookkeeping: `export-transactions history.csv
+    /// --from 2023-01-01 --to 2023-12-31`
+    /// The format is CSV, or JSON if the file has a `.json` extension, unless `--format` is given.
+    ExportTransactions {
+        path: String,
+        /// csv or json.
+        #[clap(long)]
+        format: Option<String>,
+        #[clap(flatten)]
+        filters: TransactionFilters,
+    },
     /// Request funds from the faucet to the latest address, `url` is optional, default is `http://localhost:8091/api/enqueue`
     Faucet {
         url: Option<String>,
+        #[clap(value_parser = address_argument)]
         address: Option<String>,
     },
     /// Mint more of a native token: `increase-native-token-supply 0x... 100`
     IncreaseNativeTokenSupply { token_id: String, amount: String },
+    /// Label an own address, or remove its label if none is given: `label-address
+    /// rms1qztwng6cty8cfm42nzvq099ev7udhrnk0rw8jt8vttf9kpqnxhpsx869vr3 invoice 42`
+    #[clap(trailing_var_arg = true)]
+    LabelAddress {
+        #[clap(value_parser = address_argument)]
+        address: String,
+        label: Vec<String>,
+    },
+    /// Keep an output of the account out of automatic input selection, with an optional note, or list the locked
+    /// outputs without argument: `lock-output 0x1c7a765db0c1f5eceb0ea5578585359c5b0c1ab8d958829f5990997b93f0ec7d0100
+    /// vesting plan`
+    #[clap(trailing_var_arg = true)]
+    LockOutput {
+        output_id: Option<String>,
+        note: Vec<String>,
+    },
     /// Mint a native token: `mint-native-token 100 100 --foundry-metadata-hex 0x...`
     MintNativeToken {
         circulating_supply: String,
@@ -77,6 +272,7 @@ pub enum AccountCommand {
     /// Mint an NFT to an optional bech32 encoded address: `mint-nft
     /// rms1qztwng6cty8cfm42nzvq099ev7udhrnk0rw8jt8vttf9kpqnxhpsx869vr3 "immutable metadata" "metadata"`
     MintNft {
+        #[clap(value_parser = address_argument)]
         address: Option<String>,
         #[clap(long, group = "immutable_metadata")]
         immutable_metadata_hex: Option<String>,
@@ -95,35 +291,161 @@ pub enum AccountCommand {
     },
     /// Generate a new address.
     NewAddress,
-    /// Display an output.
+    /// Attach a note to a transaction, or remove its note if none is given: `note-transaction
+    /// 0x2c5ec1ca0ba59ba6a5e3d2d8bd7a4a5bc4f03e1e3e3ee6b7ba9c2bfcc7e7a1a6 rent for March`
+    #[clap(trailing_var_arg = true)]
+    NoteTransaction { transaction_id: String, note: Vec<String> },
+    /// Display an output decoded: type, amount, native tokens, unlock conditions, features and storage deposit.
+    /// Outputs of other accounts are fetched from the node.
     Output { output_id: String },
-    /// List all outputs.
-    Outputs,
+    /// List all outputs as a table: output id, type, amount, address, unlock conditions and booked date.
+    Outputs {
+        #[clap(flatten)]
+        filters: OutputFilters,
+    },
+    /// List the transactions of the account that aren't included yet.
+    Pending,
+    /// Prepare the transaction of a command and write it to a file, to be signed offline with `sign-transaction`:
+    /// `prepare-transaction prepared.json send rms1qztwng6cty8cfm42nzvq099ev7udhrnk0rw8jt8vttf9kpqnxhpsx869vr3
+    /// 1000000`
+    #[clap(trailing_var_arg = true)]
+    PrepareTransaction {
+        path: String,
+        #[clap(required = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+    /// Reattach or promote the block of a transaction until it is included: `reattach
+    /// 0x7e8b9b0fbe6e0a8d2eb3ef1e1e8f39b9f4c6ba9e0dd5bb70e98ae3a0f8e5c8c9`
+    Reattach { block_id: String },
+    /// Never claim an incoming output, so that it returns to its sender once expired, or claim it again with `--undo`:
+    /// `reject 0x1c7a765db0c1f5eceb0ea5578585359c5b0c1ab8d958829f5990997b93f0ec7d0100`
+    Reject {
+        output_id: String,
+        #[clap(long)]
+        undo: bool,
+    },
+    /// Reissue the payload of a transaction, reattaching or promoting its block until it is included: `retry
+    /// 0x2c5ec1ca0ba59ba6a5e3d2d8bd7a4a5bc4f03e1e3e3ee6b7ba9c2bfcc7e7a1a6`
+    Retry { transaction_id: String },
     /// Send an amount to a bech32 encoded address: `send
     /// rms1qztwng6cty8cfm42nzvq099ev7udhrnk0rw8jt8vttf9kpqnxhpsx869vr3 1000000`
-    Send { address: String, amount: u64 },
+    Send {
+        #[clap(value_parser = address_argument)]
+        address: String,
+        amount: u64,
+    },
+    /// Send base coins, native tokens and NFTs to the recipients of a CSV or JSON file: `send-batch payroll.csv`
+    /// Progress is written to `<file>.report.json`, running the command again resumes after the last sent row.
+    SendBatch { path: String },
     /// Send an amount below the storage deposit minimum to a bech32 address: `send
     /// rms1qztwng6cty8cfm42nzvq099ev7udhrnk0rw8jt8vttf9kpqnxhpsx869vr3 1`
-    SendMicro { address: String, amount: u64 },
+    SendMicro {
+        #[clap(value_parser = address_argument)]
+        address: String,
+        amount: u64,
+    },
     /// Send native tokens to a bech32 address: `send-native-token
     /// rms1qztwng6cty8cfm42nzvq099ev7udhrnk0rw8jt8vttf9kpqnxhpsx869vr3
     /// 0x08e3a2f76cc934bc0cc21575b4610c1d7d4eb589ae0100000000000000000000000000000000 10`
     /// This will create an output with an expiration and storage deposit return unlock condition. To gift the storage
     /// deposit for the output, add ` true`.
     SendNativeToken {
+        #[clap(value_parser = address_argument)]
         address: String,
         token_id: String,
         amount: String,
         gift_storage_deposit: Option<bool>,
     },
     /// Send an NFT to a bech32 encoded address
-    SendNft { address: String, nft_id: String },
+    SendNft {
+        #[clap(value_parser = address_argument)]
+        address: String,
+        nft_id: String,
+    },
+    /// Send an amount to a bech32 encoded address with optional features and unlock conditions: `send-output
+    /// rms1qztwng6cty8cfm42nzvq099ev7udhrnk0rw8jt8vttf9kpqnxhpsx869vr3 1000000 --tag 0x... --timelock 1672531200`
+    /// Timestamps are given in seconds since the Unix epoch. The expiration and storage deposit return unlock
+    /// conditions return to `return_address`, which defaults to the first address of the account.
+    SendOutput {
+        #[clap(value_parser = address_argument)]
+        address: String,
+        amount: u64,
+        #[clap(long)]
+        tag: Option<String>,
+        #[clap(long, group = "metadata")]
+        metadata_hex: Option<String>,
+        #[clap(long, group = "metadata")]
+        metadata_file: Option<String>,
+        #[clap(long, value_parser = address_argument)]
+        sender: Option<String>,
+        #[clap(long)]
+        expiration: Option<u32>,
+        #[clap(long)]
+        timelock: Option<u32>,
+        #[clap(long)]
+        storage_deposit_return: Option<u64>,
+        #[clap(long, value_parser = address_argument)]
+        return_address: Option<String>,
+        #[clap(long, requires = "tagged_data")]
+        tagged_data_tag: Option<String>,
+        #[clap(long, group = "tagged_data")]
+        tagged_data_hex: Option<String>,
+        #[clap(long, group = "tagged_data")]
+        tagged_data_file: Option<String>,
+    },
+    /// Send outputs given as a JSON array in the standard DTO format, optionally sending the remainder to an address
+    /// of the account: `send-outputs outputs.json --inputs 0x...,0x...`
+    SendOutputs {
+        path: String,
+        /// Bech32 encoded address of the account receiving the remainder.
+        #[clap(long, value_parser = address_argument)]
+        remainder_address: Option<String>,
+    },
+    /// Sign a transaction prepared with `prepare-transaction` and write it to a file, to be submitted online with
+    /// `submit-transaction`: `sign-transaction prepared.json signed.json`
+    SignTransaction { prepared_path: String, signed_path: String },
+    /// Compute the minimum storage deposit of an output from the rent structure of the node, or list the deposit
+    /// locked by each output of the account with `--owned`: `storage-deposit --type nft --immutable-metadata-file
+    /// nft.json`
+    StorageDeposit {
+        #[clap(flatten)]
+        output: OutputDescription,
+        #[clap(long, conflicts_with = "output_description")]
+        owned: bool,
+    },
+    /// Submit a transaction signed with `sign-transaction`: `submit-transaction signed.json`
+    SubmitTransaction { path: String },
+    /// Send all available base coins and native tokens to a bech32 encoded address, optionally with the NFTs and
+    /// aliases of the account: `sweep rms1qztwng6cty8cfm42nzvq099ev7udhrnk0rw8jt8vttf9kpqnxhpsx869vr3 --include-nfts`
+    Sweep {
+        #[clap(value_parser = address_argument)]
+        address: String,
+        #[clap(long)]
+        include_nfts: bool,
+        #[clap(long)]
+        include_aliases: bool,
+    },
     /// Sync the account with the Tangle.
     Sync,
-    /// List the account transactions.
-    Transactions,
-    /// List the unspent outputs.
-    UnspentOutputs,
+    /// Display a transaction with its details.
+    Transaction { transaction_id: String },
+    /// List the account transactions, oldest first: date, direction, counterparty, base coin and native token changes,
+    /// inclusion state, block id and transaction id.
+    Transactions {
+        #[clap(flatten)]
+        filters: TransactionFilters,
+    },
+    /// Let automatic input selection pick an output locked with `lock-output` again: `unlock-output
+    /// 0x1c7a765db0c1f5eceb0ea5578585359c5b0c1ab8d958829f5990997b93f0ec7d0100`
+    UnlockOutput { output_id: String },
+    /// List the unspent outputs as a table, like `outputs`.
+    UnspentOutputs {
+        #[clap(flatten)]
+        filters: OutputFilters,
+    },
+    /// Wait until a transaction is included or conflicting, up to `--wait-timeout` seconds: `wait-transaction
+    /// 0x2c5ec1ca0ba59ba6a5e3d2d8bd7a4a5bc4f03e1e3e3ee6b7ba9c2bfcc7e7a1a6`
+    WaitTransaction { transaction_id: String },
     /// Cast given votes for a given event
     Vote { event_id: String, answers: Vec<u8> },
     /// Stop participating to a given event
@@ -140,6 +462,226 @@ pub enum AccountCommand {
     VotingOutput,
 }
 
+impl AccountCommand {
+    /// Whether the command signs a transaction, which needs the keys of the account.
+    pub fn signs_transaction(&self) -> bool {
+        matches!(
+            self,
+            Self::AliasSetGovernor { .. }
+                | Self::AliasSetStateController { .. }
+                | Self::AliasUpdateState { .. }
+                | Self::BurnNativeToken { .. }
+                | Self::BurnNft { .. }
+                | Self::Claim { .. }
+                | Self::Consolidate { .. }
+                | Self::CreateAliasOutput { .. }
+                | Self::DecreaseNativeTokenSupply { .. }
+                | Self::DestroyAlias { .. }
+                | Self::DestroyFoundry { .. }
+                | Self::IncreaseNativeTokenSupply { .. }
+                | Self::MintNativeToken { .. }
+                | Self::MintNft { .. }
+                | Self::Send { .. }
+                | Self::SendBatch { .. }
+                | Self::SendMicro { .. }
+                | Self::SendNativeToken { .. }
+                | Self::SendNft { .. }
+                | Self::SendOutput { .. }
+                | Self::SendOutputs { .. }
+                | Self::SignTransaction { .. }
+                | Self::Sweep { .. }
+                | Self::Vote { .. }
+                | Self::StopParticipating { .. }
+                | Self::IncreaseVotingPower { .. }
+                | Self::DecreaseVotingPower { .. }
+        )
+    }
+
+    /// Whether the command issues its transaction with the inputs chosen with `--inputs` or `--mandatory-inputs`.
+    pub fn supports_input_selection(&self) -> bool {
+        matches!(
+            self,
+            Self::AliasSetGovernor { .. }
+                | Self::AliasSetStateController { .. }
+                | Self::AliasUpdateState { .. }
+                | Self::BurnNativeToken { .. }
+                | Self::BurnNft { .. }
+                | Self::CreateAliasOutput { .. }
+                | Self::DecreaseNativeTokenSupply { .. }
+                | Self::DestroyAlias { .. }
+                | Self::DestroyFoundry { .. }
+                | Self::IncreaseNativeTokenSupply { .. }
+                | Self::MintNativeToken { .. }
+                | Self::MintNft { .. }
+                | Self::Send { .. }
+                | Self::SendBatch { .. }
+                | Self::SendMicro { .. }
+                | Self::SendNativeToken { .. }
+                | Self::SendNft { .. }
+                | Self::SendOutput { .. }
+                | Self::SendOutputs { .. }
+        )
+    }
+}
+
+/// Category of the outputs claimed by `claim`, all of them by default.
+#[derive(Debug, Clone, Default, Args)]
+#[clap(group(ArgGroup::new("claim_category").conflicts_with("output_id")))]
+pub struct ClaimCategory {
+    /// Only claim micro transactions, outputs with an amount below the storage deposit minimum.
+    #[clap(long, group = "claim_category")]
+    pub micro_transactions: bool,
+    /// Only claim outputs holding native tokens.
+    #[clap(long, group = "claim_category")]
+    pub native_tokens: bool,
+    /// Only claim NFT outputs.
+    #[clap(long, group = "claim_category")]
+    pub nfts: bool,
+    /// Only claim outputs holding base coins only.
+    #[clap(long, group = "claim_category")]
+    pub amount: bool,
+}
+
+impl From<&ClaimCategory> for OutputsToClaim {
+    fn from(category: &ClaimCategory) -> Self {
+        if category.micro_transactions {
+            Self::MicroTransactions
+        } else if category.native_tokens {
+            Self::NativeTokens
+        } else if category.nfts {
+            Self::Nfts
+        } else if category.amount {
+            Self::Amount
+        } else {
+            Self::All
+        }
+    }
+}
+
+/// Options of `consolidate`.
+#[derive(Debug, Clone, Args)]
+pub struct ConsolidationOptions {
+    /// Only consolidate if at least this many outputs can be merged.
+    #[clap(long, default_value_t = 2)]
+    pub threshold: usize,
+    /// Bech32 encoded address receiving the merged outputs, the first address of the account by default.
+    #[clap(long, value_parser = address_argument)]
+    pub to: Option<String>,
+    /// Also merge outputs holding native tokens.
+    #[clap(long)]
+    pub include_native_tokens: bool,
+    /// Maximum number of outputs merged by each transaction.
+    #[clap(long, default_value_t = INPUT_COUNT_MAX as usize)]
+    pub max_inputs: usize,
+    /// Only show the transactions and the outputs they would merge.
+    #[clap(long)]
+    pub plan: bool,
+}
+
+/// Description of the output whose storage deposit `storage-deposit` computes.
+#[derive(Debug, Clone, Args)]
+#[clap(group(ArgGroup::new("output_description").multiple(true)))]
+pub struct OutputDescription {
+    /// Type of the output: basic, alias, foundry or nft.
+    #[clap(long = "type", default_value = "basic", group = "output_description")]
+    pub kind: String,
+    /// Number of distinct native tokens held by the output.
+    #[clap(long, default_value_t = 0, group = "output_description")]
+    pub native_tokens: usize,
+    /// Hex encoded tag feature.
+    #[clap(long, group = "output_description")]
+    pub tag: Option<String>,
+    /// Hex encoded metadata feature.
+    #[clap(long, group = "output_description", group = "metadata")]
+    pub metadata_hex: Option<String>,
+    /// File holding the metadata feature.
+    #[clap(long, group = "output_description", group = "metadata")]
+    pub metadata_file: Option<String>,
+    /// Hex encoded immutable metadata feature, for NFTs, aliases and foundries.
+    #[clap(long, group = "output_description", group = "immutable_metadata")]
+    pub immutable_metadata_hex: Option<String>,
+    /// File holding the immutable metadata feature, for NFTs, aliases and foundries.
+    #[clap(long, group = "output_description", group = "immutable_metadata")]
+    pub immutable_metadata_file: Option<String>,
+    /// With a sender feature.
+    #[clap(long, group = "output_description")]
+    pub sender: bool,
+    /// With an issuer feature, for NFTs and aliases.
+    #[clap(long, group = "output_description")]
+    pub issuer: bool,
+    /// With an expiration unlock condition, for basic outputs and NFTs.
+    #[clap(long, group = "output_description")]
+    pub expiration: bool,
+    /// With a timelock unlock condition, for basic outputs and NFTs.
+    #[clap(long, group = "output_description")]
+    pub timelock: bool,
+    /// With a storage deposit return unlock condition, for basic outputs and NFTs.
+    #[clap(long, group = "output_description")]
+    pub storage_deposit_return: bool,
+}
+
+/// Filters of the listed outputs.
+#[derive(Debug, Clone, Default, Args)]
+pub struct OutputFilters {
+    /// Only outputs of this type: basic, alias, foundry or nft.
+    #[clap(long = "type")]
+    pub kind: Option<String>,
+    /// Only outputs of this bech32 encoded address of the account.
+    #[clap(long, value_parser = address_argument)]
+    pub address: Option<String>,
+    /// Only outputs holding native tokens.
+    #[clap(long)]
+    pub with_native_tokens: bool,
+    /// Only outputs that can't be spent right away: with a pending timelock, an expiration or a storage deposit
+    /// return.
+    #[clap(long)]
+    pub locked: bool,
+    /// Only outputs expiring before this date, `YYYY-MM-DD` in UTC.
+    #[clap(long)]
+    pub expiring_before: Option<String>,
+    /// Only outputs of at least this amount.
+    #[clap(long)]
+    pub min_amount: Option<u64>,
+}
+
+/// Filters of the listed transactions.
+#[derive(Debug, Clone, Default, Args)]
+pub struct TransactionFilters {
+    /// Only transactions from this date on, `YYYY-MM-DD` in UTC.
+    #[clap(long)]
+    pub from: Option<String>,
+    /// Only transactions up to this date, included, `YYYY-MM-DD` in UTC.
+    #[clap(long)]
+    pub to: Option<String>,
+    /// Only transactions in this inclusion state: pending, confirmed, conflicting or unknown-pruned.
+    #[clap(long)]
+    pub state: Option<String>,
+    /// Only transactions involving this bech32 encoded address.
+    #[clap(long, value_parser = address_argument)]
+    pub address: Option<String>,
+    /// Only transactions changing the balance of this native token.
+    #[clap(long)]
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ContactsCommand {
+    /// Add or replace a contact: `contacts add alice rms1qztwng6cty8cfm42nzvq099ev7udhrnk0rw8jt8vttf9kpqnxhpsx869vr3
+    /// --label exchange`
+    Add {
+        name: String,
+        address: String,
+        #[clap(long)]
+        label: Option<String>,
+    },
+    /// List the contacts.
+    List,
+    /// Remove a contact.
+    Remove { name: String },
+    /// Show a contact.
+    Show { name: String },
+}
+
 /// `addresses` command
 pub async fn addresses_command(account_handle: &AccountHandle) -> Result<(), Error> {
     let addresses = account_handle.addresses().await?;
@@ -155,21 +697,152 @@ pub async fn addresses_command(account_handle: &AccountHandle) -> Result<(), Err
     Ok(())
 }
 
+// `alias-set-governor` command
+pub async fn alias_set_governor_command(
+    account_handle: &AccountHandle,
+    alias_id: String,
+    address: String,
+    transaction_flags: &TransactionFlags,
+) -> Result<(), Error> {
+    let alias_id = AliasId::from_str(&alias_id)?;
+
+    log::info!("Setting governor of alias {alias_id} to {address}.");
+
+    let address = parse_network_address(&address, &account_handle.client().get_bech32_hrp().await?)?;
+    let alias_output = unspent_alias_output(account_handle, &alias_id).await?;
+    // A governance transition keeps the state index, only the unlock conditions change.
+    let alias_output_builder = AliasOutputBuilder::from(&alias_output)
+        .with_alias_id(alias_id)
+        .with_unlock_conditions(vec![
+            UnlockCondition::StateControllerAddress(StateControllerAddressUnlockCondition::new(
+                *alias_output.state_controller_address(),
+            )),
+            UnlockCondition::GovernorAddress(GovernorAddressUnlockCondition::new(address)),
+        ]);
+
+    let prepared_transaction =
+        prepare_alias_transition(account_handle, alias_output_builder, transaction_flags).await?;
+    let Some(transaction) =
+        sign_and_submit_transaction(account_handle, prepared_transaction, transaction_flags).await?
+    else {
+        return Ok(());
+    };
+
+    log::info!(
+        "Alias governance transition transaction sent:\n{:?}\n{:?}",
+        transaction.transaction_id,
+        transaction.block_id
+    );
+
+    Ok(())
+}
+
+// `alias-set-state-controller` command
+pub async fn alias_set_state_controller_command(
+    account_handle: &AccountHandle,
+    alias_id: String,
+    address: String,
+    transaction_flags: &TransactionFlags,
+) -> Result<(), Error> {
+    let alias_id = AliasId::from_str(&alias_id)?;
+
+    log::info!("Setting state controller of alias {alias_id} to {address}.");
+
+    let address = parse_network_address(&address, &account_handle.client().get_bech32_hrp().await?)?;
+    let alias_output = unspent_alias_output(account_handle, &alias_id).await?;
+    // Changing the state controller is a governance transition, it has to be signed by the governor.
+    let alias_output_builder = AliasOutputBuilder::from(&alias_output)
+        .with_alias_id(alias_id)
+        .with_unlock_conditions(vec![
+            UnlockCondition::StateControllerAddress(StateControllerAddressUnlockCondition::new(address)),
+            UnlockCondition::GovernorAddress(GovernorAddressUnlockCondition::new(*alias_output.governor_address())),
+        ]);
+
+    let prepared_transaction =
+        prepare_alias_transition(account_handle, alias_output_builder, transaction_flags).await?;
+    let Some(transaction) =
+        sign_and_submit_transaction(account_handle, prepared_transaction, transaction_flags).await?
+    else {
+        return Ok(());
+    };
+
+    log::info!(
+        "Alias governance transition transaction sent:\n{:?}\n{:?}",
+        transaction.transaction_id,
+        transaction.block_id
+    );
+
+    Ok(())
+}
+
+// `alias-update-state` command
+pub async fn alias_update_state_command(
+    account_handle: &AccountHandle,
+    alias_id: String,
+    state_metadata: Option<Vec<u8>>,
+    clear: bool,
+    transaction_flags: &TransactionFlags,
+) -> Result<(), Error> {
+    let alias_id = AliasId::from_str(&alias_id)?;
+    // Alias state metadata often anchors an identity, it is only cleared on explicit request.
+    let state_metadata = match (state_metadata, clear) {
+        (Some(state_metadata), _) => state_metadata,
+        (None, true) => Vec::new(),
+        (None, false) => {
+            return Err(Error::Miscellaneous(
+                "give the new state metadata with --state-metadata-hex or --state-metadata-file, or --clear to clear it"
+                    .to_string(),
+            ));
+        }
+    };
+
+    log::info!("Updating state of alias {alias_id}.");
+
+    let alias_output = unspent_alias_output(account_handle, &alias_id).await?;
+    // A state transition increments the state index and has to be signed by the state controller.
+    let alias_output_builder = AliasOutputBuilder::from(&alias_output)
+        .with_alias_id(alias_id)
+        .with_state_index(alias_output.state_index() + 1)
+        .with_state_metadata(state_metadata);
+
+    let prepared_transaction =
+        prepare_alias_transition(account_handle, alias_output_builder, transaction_flags).await?;
+    let Some(transaction) =
+        sign_and_submit_transaction(account_handle, prepared_transaction, transaction_flags).await?
+    else {
+        return Ok(());
+    };
+
+    log::info!(
+        "Alias state transition transaction sent:\n{:?}\n{:?}",
+        transaction.transaction_id,
+        transaction.block_id
+    );
+
+    Ok(())
+}
+
 // `burn-native-token` command
 pub async fn burn_native_token_command(
     account_handle: &AccountHandle,
     token_id: String,
     amount: String,
+    transaction_flags: &TransactionFlags,
 ) -> Result<(), Error> {
     log::info!("Burning native token {token_id} {amount}.");
 
-    let transaction = account_handle
-        .burn_native_token(
+    let prepared_transaction = account_handle
+        .prepare_burn_native_token(
             TokenId::from_str(&token_id)?,
             U256::from_dec_str(&amount).map_err(|e| Error::Miscellaneous(e.to_string()))?,
-            None,
+            Some(transaction_flags.transaction_options()?),
         )
         .await?;
+    let Some(transaction) =
+        sign_and_submit_transaction(account_handle, prepared_transaction, transaction_flags).await?
+    else {
+        return Ok(());
+    };
 
     log::info!(
         "Burning transaction sent:\n{:?}\n{:?}",
@@ -181,10 +854,24 @@ pub async fn burn_native_token_command(
 }
 
 // `burn-nft` command
-pub async fn burn_nft_command(account_handle: &AccountHandle, nft_id: String) -> Result<(), Error> {
+pub async fn burn_nft_command(
+    account_handle: &AccountHandle,
+    nft_id: String,
+    transaction_flags: &TransactionFlags,
+) -> Result<(), Error> {
     log::info!("Burning nft {nft_id}.");
 
-    let transaction = account_handle.burn_nft(NftId::from_str(&nft_id)?, None).await?;
+    let prepared_transaction = account_handle
+        .prepare_burn_nft(
+            NftId::from_str(&nft_id)?,
+            Some(transaction_flags.transaction_options()?),
+        )
+        .await?;
+    let Some(transaction) =
+        sign_and_submit_transaction(account_handle, prepared_transaction, transaction_flags).await?
+    else {
+        return Ok(());
+    };
 
     log::info!(
         "Burning transaction sent:\n{:?}\n{:?}",
@@ -196,20 +883,36 @@ pub async fn burn_nft_command(account_handle: &AccountHandle, nft_id: String) ->
 }
 
 // `balance` command
-pub async fn balance_command(account_handle: &AccountHandle) -> Result<(), Error> {
-    log::info!("{:?}", account_handle.balance().await?);
+pub async fn balance_command(account_handle: &AccountHandle, json: bool) -> Result<(), Error> {
+    let report = BalanceReport::new(account_handle).await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        log::info!("{report}");
+    }
 
     Ok(())
 }
 
 // `claim` command
-pub async fn claim_command(account_handle: &AccountHandle, output_id: Option<String>) -> Result<(), Error> {
+pub async fn claim_command(
+    account_handle: &AccountHandle,
+    output_id: Option<String>,
+    category: ClaimCategory,
+    transaction_flags: &TransactionFlags,
+) -> Result<(), Error> {
     if let Some(output_id) = output_id {
         log::info!("Claiming output {output_id}");
 
-        let transaction = account_handle
-            .claim_outputs(vec![OutputId::from_str(&output_id)?])
+        let prepared_transaction = account_handle
+            .prepare_claim_outputs(vec![OutputId::from_str(&output_id)?])
             .await?;
+        let Some(transaction) =
+            sign_and_submit_transaction(account_handle, prepared_transaction, transaction_flags).await?
+        else {
+            return Ok(());
+        };
 
         log::info!(
             "Claiming transaction sent:\n{:?}\n{:?}",
@@ -219,138 +922,645 @@ pub async fn claim_command(account_handle: &AccountHandle, output_id: Option<Str
     } else {
         log::info!("Claiming outputs.");
 
-        let output_ids = account_handle
-            .get_unlockable_outputs_with_additional_unlock_conditions(OutputsToClaim::All)
-            .await?;
+        let output_ids = outputs_to_claim(account_handle, OutputsToClaim::from(&category)).await?;
 
         if output_ids.is_empty() {
             log::info!("No outputs available to claim.");
         }
 
-        // Doing chunks of only 60, because we might need to create the double amount of outputs, because of potential
-        // storage deposit return unlock conditions and also consider the remainder output.
-        for output_ids_chunk in output_ids.chunks(60) {
-            let transaction = account_handle.claim_outputs(output_ids_chunk.to_vec()).await?;
-            log::info!(
-                "Claiming transaction sent:\n{:?}\n{:?}",
-                transaction.transaction_id,
-                transaction.block_id
-            );
-        }
+        claim_outputs(account_handle, output_ids, transaction_flags).await?;
     };
 
     Ok(())
 }
 
-// `consolidate` command
-pub async fn consolidate_command(account_handle: &AccountHandle) -> Result<(), Error> {
-    log::info!("Consolidating outputs.");
-
-    let transaction = account_handle.consolidate_outputs(true, None).await?;
-
-    log::info!(
-        "Consolidation transaction sent:\n{:?}\n{:?}",
-        transaction.transaction_id,
-        transaction.block_id
-    );
-
-    Ok(())
+/// Outputs that can be claimed, except rejected ones and ones locked with `lock-output`.
+async fn outputs_to_claim(
+    account_handle: &AccountHandle,
+    outputs_to_claim: OutputsToClaim,
+) -> Result<Vec<OutputId>, Error> {
+    let rejected_outputs = load_storage_file::<ClaimSettings>().await?.rejected_outputs;
+    let locked_outputs = load_storage_file::<LockedOutputs>().await?;
+
+    Ok(account_handle
+        .get_unlockable_outputs_with_additional_unlock_conditions(outputs_to_claim)
+        .await?
+        .into_iter()
+        .filter(|output_id| {
+            let output_id = output_id.to_string();
+            !rejected_outputs.contains(&output_id) && !locked_outputs.outputs.contains_key(&output_id)
+        })
+        .collect())
 }
 
-// `create-alias-output` command
-pub async fn create_alias_outputs_command(account_handle: &AccountHandle) -> Result<(), Error> {
-    log::info!("Creating alias output.");
+/// Claims outputs in as many transactions as needed, preparing the next transaction while the current one is signed
+/// and submitted when `--yes` is given.
+///
+/// The wallet locks the inputs of a prepared transaction until it is submitted or fails, so no input is picked twice. A
+/// failing transaction doesn't stop the next ones, they are summarized at the end.
+async fn claim_outputs(
+    account_handle: &AccountHandle,
+    output_ids: Vec<OutputId>,
+    transaction_flags: &TransactionFlags,
+) -> Result<(), Error> {
+    // Doing chunks of only 60, because we might need to create the double amount of outputs, because of potential
+    // storage deposit return unlock conditions and also consider the remainder output.
+    let mut chunks = output_ids.chunks(60).map(<[OutputId]>::to_vec).enumerate();
+    let prepare = |(index, chunk): (usize, Vec<OutputId>)| async move {
+        let output_count = chunk.len();
+        (index, output_count, account_handle.prepare_claim_outputs(chunk).await)
+    };
+    let mut prepared = match chunks.next() {
+        Some(chunk) => Some(prepare(chunk).await),
+        None => return Ok(()),
+    };
+    let mut claimed_outputs = 0;
+    let mut transactions = 0;
+    let mut failed_chunks = Vec::new();
+    // A confirmation prompt would stall the preparation of the next transaction, and a transaction written to a file
+    // ends the loop, so transactions are only prepared ahead when neither happens.
+    let prepare_ahead = transaction_flags.yes && transaction_flags.prepared_transaction_path.is_none();
+
+    while let Some((index, output_count, prepared_transaction)) = prepared.take() {
+        let submit =
+            async { sign_and_submit_transaction(account_handle, prepared_transaction?, transaction_flags).await };
+        let result = if prepare_ahead {
+            let prepare_next = async {
+                match chunks.next() {
+                    Some(chunk) => Some(prepare(chunk).await),
+                    None => None,
+                }
+            };
+            let (result, next) = tokio::join!(submit, prepare_next);
+
+            prepared = next;
+            result
+        } else {
+            submit.await
+        };
+
+        match result {
+            Ok(Some(transaction)) => {
+                log::info!(
+                    "Claiming transaction sent:\n{:?}\n{:?}",
+                    transaction.transaction_id,
+                    transaction.block_id
+                );
+                claimed_outputs += output_count;
+                transactions += 1;
+            }
+            // A prepared transaction is written to a single file, the inputs of one prepared ahead are released.
+            Ok(None) if transaction_flags.prepared_transaction_path.is_some() => {
+                if let Some((_, _, Ok(prepared_transaction))) = prepared.take() {
+                    release_inputs(account_handle, &prepared_transaction).await?;
+                }
+                return Ok(());
+            }
+            Ok(None) => {}
+            Err(e) => {
+                log::error!("Claiming {output_count} output(s) of chunk {} failed: {e}", index + 1);
+                failed_chunks.push((index + 1, output_count));
+            }
+        }
 
-    let transaction = account_handle.create_alias_output(None, None).await?;
+        if !prepare_ahead {
+            prepared = match chunks.next() {
+                Some(chunk) => Some(prepare(chunk).await),
+                None => None,
+            };
+        }
+    }
 
-    log::info!(
-        "Alias output creation transaction sent:\n{:?}\n{:?}",
-        transaction.transaction_id,
-        transaction.block_id
-    );
+    if transactions > 0 || !failed_chunks.is_empty() {
+        log::info!("Claimed {claimed_outputs} output(s) with {transactions} transaction(s).");
+    }
+    if !failed_chunks.is_empty() {
+        log::warn!(
+            "{} output(s) of {} chunk(s) weren't claimed, run `claim` again to retry them: chunk(s) {}",
+            failed_chunks
+                .iter()
+                .map(|(_, output_count)| output_count)
+                .sum::<usize>(),
+            failed_chunks.len(),
+            failed_chunks
+                .iter()
+                .map(|(chunk, _)| chunk.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
 
     Ok(())
 }
 
-// `decrease-native-token-supply` command
-pub async fn decrease_native_token_command(
+// `auto-claim` command
+pub async fn auto_claim_command(
     account_handle: &AccountHandle,
-    token_id: String,
-    amount: String,
+    window: Option<String>,
+    off: bool,
 ) -> Result<(), Error> {
-    let transaction = account_handle
-        .decrease_native_token_supply(
-            TokenId::from_str(&token_id)?,
-            U256::from_dec_str(&amount).map_err(|e| Error::Miscellaneous(e.to_string()))?,
-            None,
-        )
-        .await?;
-
-    log::info!(
-        "Native token melting transaction sent:\n{:?}\n{:?}",
-        transaction.transaction_id,
-        transaction.block_id
-    );
+    let account_index = *account_handle.read().await.index();
+    let mut claim_settings = load_storage_file::<ClaimSettings>().await?;
+
+    if off {
+        claim_settings.auto_claim_windows.remove(&account_index);
+        log::info!("Outputs won't be claimed automatically anymore.");
+    } else if let Some(window) = window {
+        let window = parse_duration(&window)?;
+        claim_settings.auto_claim_windows.insert(account_index, window);
+        log::info!(
+            "Outputs expiring within {} will be claimed when syncing.",
+            format_duration(window)
+        );
+    } else {
+        match claim_settings.auto_claim_windows.get(&account_index) {
+            Some(window) => log::info!(
+                "Outputs expiring within {} are claimed when syncing.",
+                format_duration(*window)
+            ),
+            None => log::info!("Outputs aren't claimed automatically."),
+        }
+        return Ok(());
+    }
 
-    Ok(())
+    save_storage_file(&claim_settings).await
 }
 
-// `destroy-alias` command
-pub async fn destroy_alias_command(account_handle: &AccountHandle, alias_id: String) -> Result<(), Error> {
-    log::info!("Destroying alias {alias_id}.");
-
-    let transaction = account_handle
-        .destroy_alias(AliasId::from_str(&alias_id)?, None)
+// `claimable` command
+pub async fn claimable_command(account_handle: &AccountHandle, expiring_within: Option<String>) -> Result<(), Error> {
+    let mut output_ids = account_handle
+        .get_unlockable_outputs_with_additional_unlock_conditions(OutputsToClaim::All)
         .await?;
 
-    log::info!(
-        "Destroying alias transaction sent:\n{:?}\n{:?}",
-        transaction.transaction_id,
-        transaction.block_id
-    );
+    if let Some(expiring_within) = &expiring_within {
+        let window = parse_duration(expiring_within)?;
+        output_ids = expiring_outputs(account_handle, output_ids, window).await;
 
-    Ok(())
-}
+        if !output_ids.is_empty() {
+            log::warn!(
+                "{} output(s) expire within {}, claim them before they return to their sender.",
+                output_ids.len(),
+                format_duration(window)
+            );
+        }
+    }
 
-// `destroy-foundry` command
-pub async fn destroy_foundry_command(account_handle: &AccountHandle, foundry_id: String) -> Result<(), Error> {
-    log::info!("Destroying foundry {foundry_id}.");
+    if output_ids.is_empty() {
+        log::info!("No outputs available to claim.");
+        return Ok(());
+    }
 
-    let transaction = account_handle
-        .destroy_foundry(FoundryId::from_str(&foundry_id)?, None)
-        .await?;
+    let rejected_outputs = load_storage_file::<ClaimSettings>().await?.rejected_outputs;
+    let account_addresses = account_addresses(account_handle).await?;
+    let address_book = load_storage_file::<AddressBook>().await?;
+    let bech32_hrp = account_handle.client().get_bech32_hrp().await?;
+    let context = OutputContext {
+        bech32_hrp: &bech32_hrp,
+        rent_structure: account_handle.client().get_rent_structure().await?,
+        account_addresses: &account_addresses,
+        address_book: &address_book,
+    };
 
-    log::info!(
-        "Destroying foundry transaction sent:\n{:?}\n{:?}",
-        transaction.transaction_id,
-        transaction.block_id
-    );
+    for output_id in output_ids {
+        let Some(output_data) = account_handle.get_output(&output_id).await else {
+            continue;
+        };
+        log::info!(
+            "{}{}",
+            describe_claimable_output(&output_id, &output_data.output, &context),
+            if rejected_outputs.contains(&output_id.to_string()) {
+                "\nRejected, won't be claimed"
+            } else {
+                ""
+            }
+        );
+    }
 
     Ok(())
 }
 
-// `faucet` command
-pub async fn faucet_command(
+// `consolidate` command
+pub async fn consolidate_command(
     account_handle: &AccountHandle,
-    url: Option<String>,
-    address: Option<String>,
+    options: ConsolidationOptions,
+    transaction_flags: &TransactionFlags,
 ) -> Result<(), Error> {
-    let address = if let Some(address) = address {
-        address
-    } else {
-        match account_handle.addresses().await?.last() {
-            Some(address) => address.address().to_bech32(),
-            None => return Err(Error::NoAddressForFaucet),
-        }
-    };
-    let faucet_url = match &url {
-        Some(faucet_url) => faucet_url,
-        None => "http://localhost:8091/api/enqueue",
-    };
+    if !(2..=INPUT_COUNT_MAX as usize).contains(&options.max_inputs) {
+        return Err(Error::Miscellaneous(format!(
+            "max inputs must be between 2 and {INPUT_COUNT_MAX}"
+        )));
+    }
 
-    log::info!("{}", request_funds_from_faucet(faucet_url, &address).await?);
+    let token_supply = account_handle.client().get_token_supply().await?;
+    let bech32_hrp = account_handle.client().get_bech32_hrp().await?;
+    let address = match &options.to {
+        Some(address) => Address::try_from_bech32(address)?.1,
+        None => first_address(account_handle).await?,
+    };
 
-    Ok(())
+    let locked_outputs = load_storage_file::<LockedOutputs>().await?;
+    // Only outputs that an address unlock condition alone locks can be merged right away, locked ones are left out.
+    let outputs = account_handle
+        .unspent_outputs(None)
+        .await?
+        .into_iter()
+        .filter(|output_data| !locked_outputs.outputs.contains_key(&output_data.output_id.to_string()))
+        .filter(|output_data| match &output_data.output {
+            Output::Basic(basic_output) => {
+                basic_output.unlock_conditions().len() == 1
+                    && (options.include_native_tokens || basic_output.native_tokens().is_empty())
+            }
+            _ => false,
+        })
+        .collect::<Vec<_>>();
+
+    if outputs.len() < options.threshold {
+        log::info!(
+            "{} output(s) to consolidate, below the threshold of {}.",
+            outputs.len(),
+            options.threshold
+        );
+        return Ok(());
+    }
+
+    // A single output already held by the target address doesn't need a transaction.
+    let chunks = outputs
+        .chunks(options.max_inputs)
+        .filter(|chunk| chunk.len() > 1 || chunk[0].address != address)
+        .collect::<Vec<_>>();
+
+    if chunks.is_empty() {
+        log::info!(
+            "The outputs are already consolidated into {}.",
+            address.to_bech32(&bech32_hrp)
+        );
+        return Ok(());
+    }
+
+    let mut transaction_outputs = Vec::new();
+
+    for chunk in &chunks {
+        let mut native_tokens = BTreeMap::<TokenId, U256>::new();
+
+        for output_data in chunk.iter() {
+            if let Some(output_native_tokens) = output_data.output.native_tokens() {
+                for native_token in output_native_tokens.iter() {
+                    *native_tokens.entry(*native_token.token_id()).or_default() += *native_token.amount();
+                }
+            }
+        }
+
+        let native_tokens = native_tokens
+            .into_iter()
+            .map(|(token_id, amount)| NativeToken::new(token_id, amount))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        transaction_outputs.push(
+            BasicOutputBuilder::new_with_amount(chunk.iter().map(|output_data| output_data.output.amount()).sum())?
+                .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(address)))
+                .with_native_tokens(native_tokens)
+                .finish_output(token_supply)?,
+        );
+    }
+
+    if options.plan {
+        log::info!(
+            "Consolidating {} output(s) into {} with {} transaction(s).",
+            chunks.iter().map(|chunk| chunk.len()).sum::<usize>(),
+            address.to_bech32(&bech32_hrp),
+            chunks.len()
+        );
+
+        for (index, (chunk, output)) in chunks.iter().zip(&transaction_outputs).enumerate() {
+            let mut description = format!(
+                "Transaction {}: {} output(s), amount {}, {} native token(s)",
+                index + 1,
+                chunk.len(),
+                output.amount(),
+                output.native_tokens().map_or(0, |native_tokens| native_tokens.len())
+            );
+
+            for output_data in chunk.iter() {
+                description.push_str(&format!(
+                    "\n  {} ({})",
+                    output_data.output_id,
+                    output_data.output.amount()
+                ));
+            }
+            log::info!("{description}");
+        }
+
+        return Ok(());
+    }
+
+    log::info!("Consolidating outputs.");
+
+    for (chunk, output) in chunks.into_iter().zip(transaction_outputs) {
+        let prepared_transaction = account_handle
+            .prepare_transaction(
+                vec![output],
+                Some(TransactionOptions {
+                    custom_inputs: Some(chunk.iter().map(|output_data| output_data.output_id).collect()),
+                    ..Default::default()
+                }),
+            )
+            .await?;
+        let Some(transaction) =
+            sign_and_submit_transaction(account_handle, prepared_transaction, transaction_flags).await?
+        else {
+            return Ok(());
+        };
+
+        log::info!(
+            "Consolidation transaction sent:\n{:?}\n{:?}",
+            transaction.transaction_id,
+            transaction.block_id
+        );
+    }
+
+    Ok(())
+}
+
+// `contacts` command
+pub async fn contacts_command(account_handle: &AccountHandle, command: ContactsCommand) -> Result<(), Error> {
+    let mut address_book = load_storage_file::<AddressBook>().await?;
+
+    match command {
+        ContactsCommand::Add { name, address, label } => {
+            let name = name.trim_start_matches('@').to_string();
+            let (bech32_hrp, _) = Address::try_from_bech32(&address)?;
+
+            if bech32_hrp != account_handle.client().get_bech32_hrp().await? {
+                return Err(Error::Miscellaneous(format!(
+                    "address {address} doesn't belong to the network of the account"
+                )));
+            }
+
+            address_book.contacts.insert(name.clone(), Contact { address, label });
+            save_storage_file(&address_book).await?;
+
+            log::info!("Contact @{name} saved.");
+        }
+        ContactsCommand::List => {
+            if address_book.contacts.is_empty() {
+                log::info!("No contacts found");
+            }
+            for (name, contact) in &address_book.contacts {
+                print_contact(name, contact);
+            }
+        }
+        ContactsCommand::Remove { name } => {
+            let name = name.trim_start_matches('@');
+
+            address_book.get(name)?;
+            address_book.contacts.remove(name);
+            save_storage_file(&address_book).await?;
+
+            log::info!("Contact @{name} removed.");
+        }
+        ContactsCommand::Show { name } => {
+            let name = name.trim_start_matches('@');
+
+            print_contact(name, address_book.get(name)?);
+        }
+    }
+
+    Ok(())
+}
+
+// `create-alias-output` command
+pub async fn create_alias_outputs_command(
+    account_handle: &AccountHandle,
+    state_controller: Option<String>,
+    governor: Option<String>,
+    state_metadata: Option<Vec<u8>>,
+    immutable_metadata: Option<Vec<u8>>,
+    issuer: Option<String>,
+    transaction_flags: &TransactionFlags,
+) -> Result<(), Error> {
+    log::info!("Creating alias output.");
+
+    let rent_structure = account_handle.client().get_rent_structure().await?;
+    let token_supply = account_handle.client().get_token_supply().await?;
+    let bech32_hrp = account_handle.client().get_bech32_hrp().await?;
+    let first_address = first_address(account_handle).await?;
+    let state_controller = match state_controller {
+        Some(state_controller) => parse_network_address(&state_controller, &bech32_hrp)?,
+        None => first_address,
+    };
+    let governor = match governor {
+        Some(governor) => parse_network_address(&governor, &bech32_hrp)?,
+        None => first_address,
+    };
+
+    let mut alias_output_builder =
+        AliasOutputBuilder::new_with_minimum_storage_deposit(rent_structure, AliasId::null())?
+            .with_state_index(0)
+            .with_foundry_counter(0)
+            .add_unlock_condition(UnlockCondition::StateControllerAddress(
+                StateControllerAddressUnlockCondition::new(state_controller),
+            ))
+            .add_unlock_condition(UnlockCondition::GovernorAddress(GovernorAddressUnlockCondition::new(
+                governor,
+            )));
+
+    if let Some(state_metadata) = state_metadata {
+        alias_output_builder = alias_output_builder.with_state_metadata(state_metadata);
+    }
+    if let Some(issuer) = issuer {
+        alias_output_builder = alias_output_builder.add_immutable_feature(Feature::Issuer(IssuerFeature::new(
+            parse_network_address(&issuer, &bech32_hrp)?,
+        )));
+    }
+    if let Some(immutable_metadata) = immutable_metadata {
+        alias_output_builder =
+            alias_output_builder.add_immutable_feature(Feature::Metadata(MetadataFeature::new(immutable_metadata)?));
+    }
+
+    let outputs = vec![alias_output_builder.finish_output(token_supply)?];
+    let prepared_transaction = account_handle
+        .prepare_transaction(outputs, Some(transaction_flags.transaction_options()?))
+        .await?;
+    let Some(transaction) =
+        sign_and_submit_transaction(account_handle, prepared_transaction, transaction_flags).await?
+    else {
+        return Ok(());
+    };
+
+    log::info!(
+        "Alias output creation transaction sent:\n{:?}\n{:?}",
+        transaction.transaction_id,
+        transaction.block_id
+    );
+
+    Ok(())
+}
+
+// `decrease-native-token-supply` command
+pub async fn decrease_native_token_command(
+    account_handle: &AccountHandle,
+    token_id: String,
+    amount: String,
+    transaction_flags: &TransactionFlags,
+) -> Result<(), Error> {
+    let prepared_transaction = account_handle
+        .prepare_decrease_native_token_supply(
+            TokenId::from_str(&token_id)?,
+            U256::from_dec_str(&amount).map_err(|e| Error::Miscellaneous(e.to_string()))?,
+            Some(transaction_flags.transaction_options()?),
+        )
+        .await?;
+    let Some(transaction) =
+        sign_and_submit_transaction(account_handle, prepared_transaction, transaction_flags).await?
+    else {
+        return Ok(());
+    };
+
+    log::info!(
+        "Native token melting transaction sent:\n{:?}\n{:?}",
+        transaction.transaction_id,
+        transaction.block_id
+    );
+
+    Ok(())
+}
+
+// `destroy-alias` command
+pub async fn destroy_alias_command(
+    account_handle: &AccountHandle,
+    alias_id: String,
+    transaction_flags: &TransactionFlags,
+) -> Result<(), Error> {
+    log::info!("Destroying alias {alias_id}.");
+
+    let prepared_transaction = account_handle
+        .prepare_destroy_alias(
+            AliasId::from_str(&alias_id)?,
+            Some(transaction_flags.transaction_options()?),
+        )
+        .await?;
+    let Some(transaction) =
+        sign_and_submit_transaction(account_handle, prepared_transaction, transaction_flags).await?
+    else {
+        return Ok(());
+    };
+
+    log::info!(
+        "Destroying alias transaction sent:\n{:?}\n{:?}",
+        transaction.transaction_id,
+        transaction.block_id
+    );
+
+    Ok(())
+}
+
+// `destroy-foundry` command
+pub async fn destroy_foundry_command(
+    account_handle: &AccountHandle,
+    foundry_id: String,
+    transaction_flags: &TransactionFlags,
+) -> Result<(), Error> {
+    log::info!("Destroying foundry {foundry_id}.");
+
+    let prepared_transaction = account_handle
+        .prepare_destroy_foundry(
+            FoundryId::from_str(&foundry_id)?,
+            Some(transaction_flags.transaction_options()?),
+        )
+        .await?;
+    let Some(transaction) =
+        sign_and_submit_transaction(account_handle, prepared_transaction, transaction_flags).await?
+    else {
+        return Ok(());
+    };
+
+    log::info!(
+        "Destroying foundry transaction sent:\n{:?}\n{:?}",
+        transaction.transaction_id,
+        transaction.block_id
+    );
+
+    Ok(())
+}
+
+// `export-transactions` command
+pub async fn export_transactions_command(
+    account_handle: &AccountHandle,
+    path: String,
+    format: Option<String>,
+    filters: TransactionFilters,
+) -> Result<(), Error> {
+    let format = format.unwrap_or_else(|| {
+        if path.ends_with(".json") {
+            "json".to_string()
+        } else {
+            "csv".to_string()
+        }
+    });
+
+    if format != "csv" && format != "json" {
+        return Err(Error::Miscellaneous(format!(
+            "unknown export format \"{format}\", expected csv or json"
+        )));
+    }
+
+    let base_token = account_handle.client().get_info().await?.node_info.base_token;
+    let address_book = load_storage_file::<AddressBook>().await?;
+    let labels = load_storage_file::<Labels>().await?;
+    let bech32_hrp = account_handle.client().get_bech32_hrp().await?;
+    let mut native_token_decimals = BTreeMap::new();
+    let mut events = Vec::new();
+
+    for (transaction, summary) in filtered_transactions(account_handle, &filters).await? {
+        for token_id in summary.native_token_deltas.keys() {
+            if !native_token_decimals.contains_key(token_id) {
+                if let Some(decimals) = token_decimals(account_handle.client(), token_id).await {
+                    native_token_decimals.insert(*token_id, decimals);
+                }
+            }
+        }
+        events.extend(summary.events(
+            &base_token.ticker_symbol,
+            base_token.decimals,
+            &native_token_decimals,
+            describe_address(summary.counterparty, &bech32_hrp, &address_book),
+            transaction_note(&labels, &transaction),
+        ));
+    }
+
+    if format == "json" {
+        write_json_file(&path, &events).await?;
+    } else {
+        tokio::fs::write(&path, events_to_csv(&events)).await?;
+    }
+
+    log::info!("Exported {} event(s) to \"{path}\".", events.len());
+
+    Ok(())
+}
+
+// `faucet` command
+pub async fn faucet_command(
+    account_handle: &AccountHandle,
+    url: Option<String>,
+    address: Option<String>,
+) -> Result<(), Error> {
+    let address = if let Some(address) = address {
+        address
+    } else {
+        match account_handle.addresses().await?.last() {
+            Some(address) => address.address().to_bech32(),
+            None => return Err(Error::NoAddressForFaucet),
+        }
+    };
+    let faucet_url = match &url {
+        Some(faucet_url) => faucet_url,
+        None => "http://localhost:8091/api/enqueue",
+    };
+
+    log::info!("{}", request_funds_from_faucet(faucet_url, &address).await?);
+
+    Ok(())
 }
 
 // `increase-native-token-supply` command
@@ -358,31 +1568,105 @@ pub async fn increase_native_token_command(
     account_handle: &AccountHandle,
     token_id: String,
     amount: String,
+    transaction_flags: &TransactionFlags,
 ) -> Result<(), Error> {
-    let mint_transaction = account_handle
-        .increase_native_token_supply(
+    let prepared_transaction = account_handle
+        .prepare_increase_native_token_supply(
             TokenId::from_str(&token_id)?,
             U256::from_dec_str(&amount).map_err(|e| Error::Miscellaneous(e.to_string()))?,
             None,
-            None,
+            Some(transaction_flags.transaction_options()?),
         )
         .await?;
+    let Some(transaction) =
+        sign_and_submit_transaction(account_handle, prepared_transaction, transaction_flags).await?
+    else {
+        return Ok(());
+    };
 
     log::info!(
         "Minting more native token transaction sent:\n{:?}\n{:?}",
-        mint_transaction.transaction.transaction_id,
-        mint_transaction.transaction.block_id
+        transaction.transaction_id,
+        transaction.block_id
     );
 
     Ok(())
 }
 
+// `label-address` command
+pub async fn label_address_command(
+    account_handle: &AccountHandle,
+    address: String,
+    label: Vec<String>,
+) -> Result<(), Error> {
+    let (_, parsed_address) = Address::try_from_bech32(&address)?;
+
+    if !account_addresses(account_handle).await?.contains(&parsed_address) {
+        return Err(Error::Miscellaneous(format!(
+            "{address} is not an address of the account"
+        )));
+    }
+
+    let mut labels = load_storage_file::<Labels>().await?;
+
+    set_or_remove(&mut labels.addresses, address.clone(), label);
+    save_storage_file(&labels).await?;
+
+    match labels.addresses.get(&address) {
+        Some(label) => log::info!("Address {address} labelled \"{label}\"."),
+        None => log::info!("Label of address {address} removed."),
+    }
+
+    Ok(())
+}
+
+// `lock-output` command
+pub async fn lock_output_command(
+    account_handle: &AccountHandle,
+    output_id: Option<String>,
+    note: Vec<String>,
+) -> Result<(), Error> {
+    let mut locked_outputs = load_storage_file::<LockedOutputs>().await?;
+
+    let Some(output_id) = output_id else {
+        if locked_outputs.outputs.is_empty() {
+            log::info!("No locked outputs.");
+        }
+        for (output_id, note) in &locked_outputs.outputs {
+            if note.is_empty() {
+                log::info!("{output_id}");
+            } else {
+                log::info!("{output_id}: {note}");
+            }
+        }
+        return Ok(());
+    };
+    let output_id = OutputId::from_str(&output_id)?;
+
+    match account_handle.get_output(&output_id).await {
+        Some(output_data) if !output_data.is_spent => {}
+        _ => {
+            return Err(Error::Miscellaneous(format!(
+                "output {output_id} isn't an unspent output of the account"
+            )));
+        }
+    }
+
+    locked_outputs.outputs.insert(output_id.to_string(), note.join(" "));
+    save_storage_file(&locked_outputs).await?;
+
+    log::info!("Output {output_id} locked, only `--inputs` or `--mandatory-inputs` can spend it.");
+
+    Ok(())
+}
+
 // `mint-native-token` command
 pub async fn mint_native_token_command(
     account_handle: &AccountHandle,
     circulating_supply: String,
     maximum_supply: String,
     foundry_metadata: Option<Vec<u8>>,
+    transaction_flags: &TransactionFlags,
 ) -> Result<(), Error> {
     let native_token_options = NativeTokenOptions {
         alias_id: None,
@@ -391,18 +1675,27 @@ pub async fn mint_native_token_command(
         foundry_metadata,
     };
 
-    let mint_transaction = account_handle.mint_native_token(native_token_options, None).await?;
+    let prepared_mint_transaction = account_handle
+        .prepare_mint_native_token(native_token_options, Some(transaction_flags.transaction_options()?))
+        .await?;
+    let Some(transaction) =
+        sign_and_submit_transaction(account_handle, prepared_mint_transaction.transaction, transaction_flags).await?
+    else {
+        return Ok(());
+    };
 
     log::info!(
-        "Native token minting transaction sent:\n{:?}\n{:?}",
-        mint_transaction.transaction.transaction_id,
-        mint_transaction.transaction.block_id
+        "Native token {} minting transaction sent:\n{:?}\n{:?}",
+        prepared_mint_transaction.token_id,
+        transaction.transaction_id,
+        transaction.block_id
     );
 
     Ok(())
 }
 
 // `mint-nft` command
+#[allow(clippy::too_many_arguments)]
 pub async fn mint_nft_command(
     account_handle: &AccountHandle,
     address: Option<String>,
@@ -411,7 +1704,14 @@ pub async fn mint_nft_command(
     tag: Option<String>,
     sender: Option<String>,
     issuer: Option<String>,
+    transaction_flags: &TransactionFlags,
 ) -> Result<(), Error> {
+    let bech32_hrp = account_handle.client().get_bech32_hrp().await?;
+
+    for address in [&address, &sender, &issuer].into_iter().flatten() {
+        parse_network_address(address, &bech32_hrp)?;
+    }
+
     let tag = if let Some(hex) = tag {
         Some(prefix_hex::decode(&hex).map_err(|e| Error::Miscellaneous(e.to_string()))?)
     } else {
@@ -425,7 +1725,14 @@ pub async fn mint_nft_command(
         immutable_metadata,
         metadata,
     }];
-    let transaction = account_handle.mint_nfts(nft_options, None).await?;
+    let prepared_transaction = account_handle
+        .prepare_mint_nfts(nft_options, Some(transaction_flags.transaction_options()?))
+        .await?;
+    let Some(transaction) =
+        sign_and_submit_transaction(account_handle, prepared_transaction, transaction_flags).await?
+    else {
+        return Ok(());
+    };
 
     log::info!(
         "NFT minting transaction sent:\n{:?}\n{:?}",
@@ -436,49 +1743,810 @@ pub async fn mint_nft_command(
     Ok(())
 }
 
-// `new-address` command
-pub async fn new_address_command(account_handle: &AccountHandle) -> Result<(), Error> {
-    let address = account_handle.generate_addresses(1, None).await?;
-
-    print_address(account_handle, &address[0]).await?;
+// `new-address` command
+pub async fn new_address_command(account_handle: &AccountHandle) -> Result<(), Error> {
+    let address = account_handle.generate_addresses(1, None).await?;
+
+    print_address(account_handle, &address[0]).await?;
+
+    Ok(())
+}
+
+/// `note-transaction` command
+pub async fn note_transaction_command(
+    account_handle: &AccountHandle,
+    transaction_id: String,
+    note: Vec<String>,
+) -> Result<(), Error> {
+    let transaction_id = TransactionId::from_str(&transaction_id)?;
+
+    if account_handle.get_transaction(&transaction_id).await.is_none() {
+        return Err(Error::Miscellaneous(format!(
+            "transaction {transaction_id} not found in the account"
+        )));
+    }
+
+    let mut labels = load_storage_file::<Labels>().await?;
+
+    set_or_remove(&mut labels.transactions, transaction_id.to_string(), note);
+    save_storage_file(&labels).await?;
+
+    match labels.transactions.get(&transaction_id.to_string()) {
+        Some(note) => log::info!("Transaction {transaction_id} noted \"{note}\"."),
+        None => log::info!("Note of transaction {transaction_id} removed."),
+    }
+
+    Ok(())
+}
+
+// `output` command
+pub async fn output_command(account_handle: &AccountHandle, output_id: String) -> Result<(), Error> {
+    let output_id = OutputId::from_str(&output_id)?;
+    // Outputs that don't belong to the account are fetched from the node.
+    let (output, is_spent, owned) = match account_handle.get_output(&output_id).await {
+        Some(output_data) => (output_data.output, output_data.is_spent, true),
+        None => {
+            let output_response = account_handle.client().get_output(&output_id).await?;
+
+            (
+                Output::try_from_dto_unverified(&output_response.output)?,
+                output_response.metadata.is_spent,
+                false,
+            )
+        }
+    };
+    let account_addresses = account_addresses(account_handle).await?;
+    let address_book = load_storage_file::<AddressBook>().await?;
+    let bech32_hrp = account_handle.client().get_bech32_hrp().await?;
+    let context = OutputContext {
+        bech32_hrp: &bech32_hrp,
+        rent_structure: account_handle.client().get_rent_structure().await?,
+        account_addresses: &account_addresses,
+        address_book: &address_book,
+    };
+
+    log::info!(
+        "{}\nSpent: {is_spent}\nControlled by: {}{}",
+        describe_output(&output_id, &output, &context),
+        output_address(&output).map_or_else(|| "-".to_string(), |address| context.address(&address)),
+        if owned {
+            ""
+        } else {
+            "\nNot an output of the account, fetched from the node."
+        }
+    );
+
+    Ok(())
+}
+
+/// `outputs` command
+pub async fn outputs_command(account_handle: &AccountHandle, filters: OutputFilters) -> Result<(), Error> {
+    print_outputs(account_handle, account_handle.outputs(None).await?, &filters).await
+}
+
+// `sign-transaction` command
+pub async fn sign_transaction_command(
+    account_handle: &AccountHandle,
+    prepared_path: String,
+    signed_path: String,
+    transaction_flags: &TransactionFlags,
+) -> Result<(), Error> {
+    // No node is needed to sign, the prepared transaction is therefore not verified against the protocol parameters.
+    let mut prepared_transaction = PreparedTransactionData::try_from_dto_unverified(
+        &read_json_file::<PreparedTransactionDataDto>(&prepared_path).await?,
+    )?;
+    set_input_chains(account_handle, &mut prepared_transaction).await?;
+
+    if !confirm_transaction(
+        account_handle,
+        &prepared_transaction,
+        transaction_flags,
+        "Sign the transaction?",
+        true,
+    )
+    .await?
+    {
+        return Ok(());
+    }
+
+    let signed_transaction = account_handle.sign_transaction_essence(&prepared_transaction).await?;
+
+    write_json_file(&signed_path, &SignedTransactionDataDto::from(&signed_transaction)).await?;
+
+    log::info!("Signed transaction written to \"{signed_path}\", submit it with `submit-transaction`.");
+
+    Ok(())
+}
+
+// `pending` command
+pub async fn pending_command(account_handle: &AccountHandle) -> Result<(), Error> {
+    transactions_command(
+        account_handle,
+        TransactionFilters {
+            state: Some("pending".to_string()),
+            ..Default::default()
+        },
+    )
+    .await
+}
+
+// `reattach` command
+pub async fn reattach_command(account_handle: &AccountHandle, block_id: String) -> Result<(), Error> {
+    let blocks = account_handle
+        .client()
+        .retry_until_included(&BlockId::from_str(&block_id)?, None, None)
+        .await?;
+
+    // The last block is the one that got included, the previous ones are its reattachments.
+    if let Some((included_block_id, _)) = blocks.last() {
+        log::info!("Block included: {included_block_id}");
+    }
+
+    account_handle.sync(None).await?;
+
+    Ok(())
+}
+
+// `reject` command
+pub async fn reject_command(account_handle: &AccountHandle, output_id: String, undo: bool) -> Result<(), Error> {
+    let output_id = OutputId::from_str(&output_id)?;
+
+    if account_handle.get_output(&output_id).await.is_none() {
+        return Err(Error::Miscellaneous(format!(
+            "output {output_id} not found in the account"
+        )));
+    }
+
+    let mut claim_settings = load_storage_file::<ClaimSettings>().await?;
+
+    if undo {
+        claim_settings.rejected_outputs.remove(&output_id.to_string());
+        log::info!("Output {output_id} will be claimed again.");
+    } else {
+        claim_settings.rejected_outputs.insert(output_id.to_string());
+        log::info!("Output {output_id} rejected, it won't be claimed.");
+    }
+
+    save_storage_file(&claim_settings).await
+}
+
+// `retry` command
+pub async fn retry_command(account_handle: &AccountHandle, transaction_id: String) -> Result<(), Error> {
+    let transaction_id = TransactionId::from_str(&transaction_id)?;
+    let block_id = account_handle
+        .retry_transaction_until_included(&transaction_id, None, None)
+        .await?;
+
+    log::info!("Transaction {transaction_id} included in block {block_id}");
+
+    account_handle.sync(None).await?;
+
+    Ok(())
+}
+
+// `send` command
+pub async fn send_command(
+    account_handle: &AccountHandle,
+    address: String,
+    amount: u64,
+    transaction_flags: &TransactionFlags,
+) -> Result<(), Error> {
+    let outputs = vec![AddressWithAmount { address, amount }];
+    let prepared_transaction = account_handle
+        .prepare_send_amount(outputs, Some(transaction_flags.transaction_options()?))
+        .await?;
+    let Some(transaction) =
+        sign_and_submit_transaction(account_handle, prepared_transaction, transaction_flags).await?
+    else {
+        return Ok(());
+    };
+
+    log::info!(
+        "Transaction sent:\n{:?}\n{:?}",
+        transaction.transaction_id,
+        transaction.block_id
+    );
+
+    Ok(())
+}
+
+// `send-batch` command
+pub async fn send_batch_command(
+    account_handle: &AccountHandle,
+    path: String,
+    transaction_flags: &TransactionFlags,
+) -> Result<(), Error> {
+    // One output is kept for the remainder and half of the inputs for the base coins funding the outputs.
+    const MAX_OUTPUTS_PER_TRANSACTION: usize = OUTPUT_COUNT_MAX as usize - 1;
+    const MAX_NFTS_PER_TRANSACTION: usize = INPUT_COUNT_MAX as usize / 2;
+
+    let address_book = load_storage_file::<AddressBook>().await?;
+    let rows = read_batch_file(&path)
+        .await?
+        .into_iter()
+        .map(|mut row| {
+            row.address = address_book.resolve(&row.address)?;
+            Ok(row)
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    let mut report = BatchReport::load(&path).await?;
+
+    // Refresh the inclusion state of earlier transactions, rows of conflicting ones are sent again.
+    if report
+        .rows
+        .iter()
+        .any(|row_report| row_report.is_sent() && row_report.inclusion_state != Some(InclusionState::Confirmed))
+    {
+        account_handle.sync(None).await?;
+
+        for row_report in report.rows.iter_mut().filter(|row_report| row_report.is_sent()) {
+            if let Some(transaction_id) = &row_report.transaction_id {
+                if let Some(transaction) = account_handle
+                    .get_transaction(&TransactionId::from_str(transaction_id)?)
+                    .await
+                {
+                    row_report.inclusion_state = Some(transaction.inclusion_state);
+                }
+            }
+        }
+        report.save(&path).await?;
+
+        if report
+            .rows
+            .iter()
+            .any(|row_report| row_report.inclusion_state == Some(InclusionState::Pending))
+        {
+            log::warn!("Some rows were sent in transactions that are still pending, they are not sent again.");
+        }
+    }
+
+    let mut sent_rows = report.sent_rows();
+    let bech32_hrp = account_handle.client().get_bech32_hrp().await?;
+    let rent_structure = account_handle.client().get_rent_structure().await?;
+    let token_supply = account_handle.client().get_token_supply().await?;
+    let unspent_outputs = account_handle.unspent_outputs(None).await?;
+
+    // Validate every row before sending anything.
+    let mut outputs = Vec::new();
+    let mut errors = Vec::new();
+
+    for (index, row) in rows.iter().enumerate() {
+        let row_number = index + 1;
+
+        // Rows are matched by content, so that editing the batch file between runs doesn't skip the wrong rows.
+        if let Some(count) = sent_rows.get_mut(&row.hash()).filter(|count| **count > 0) {
+            *count -= 1;
+            continue;
+        }
+
+        match batch_row_output(row, &bech32_hrp, rent_structure, token_supply, &unspent_outputs) {
+            Ok(output) => outputs.push((row_number, output)),
+            Err(e) => errors.push(format!("row {row_number}: {e}")),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(Error::Miscellaneous(format!(
+            "invalid batch file:\n{}",
+            errors.join("\n")
+        )));
+    }
+    if outputs.is_empty() {
+        log::info!("No rows left to send.");
+        return Ok(());
+    }
+
+    let pending_rows = outputs.len();
+    let mut chunks: Vec<Vec<(usize, Output)>> = Vec::new();
+    let mut chunk_nfts = 0;
+
+    for (row_number, output) in outputs {
+        let is_nft = matches!(output, Output::Nft(_));
+
+        match chunks.last_mut() {
+            Some(chunk)
+                if chunk.len() < MAX_OUTPUTS_PER_TRANSACTION && !(is_nft && chunk_nfts == MAX_NFTS_PER_TRANSACTION) =>
+            {
+                chunk.push((row_number, output))
+            }
+            _ => {
+                chunks.push(vec![(row_number, output)]);
+                chunk_nfts = 0;
+            }
+        }
+        if is_nft {
+            chunk_nfts += 1;
+        }
+    }
+
+    log::info!("Sending {pending_rows} rows in {} transactions.", chunks.len());
+
+    let mut chunks = VecDeque::from(chunks);
+
+    while let Some(chunk) = chunks.pop_front() {
+        let (row_numbers, outputs): (Vec<usize>, Vec<Output>) = chunk.into_iter().unzip();
+
+        // The report is saved after every transaction so that a failure never leads to rows being sent twice.
+        let transaction = match account_handle
+            .prepare_transaction(outputs.clone(), Some(transaction_flags.transaction_options()?))
+            .await
+        {
+            Ok(prepared_transaction) => {
+                sign_and_submit_transaction(account_handle, prepared_transaction, transaction_flags).await
+            }
+            // Chunk sizes are estimates, a chunk whose funding needs more inputs than a transaction can have is split.
+            Err(WalletError::Client(error))
+                if matches!(*error, ClientError::ConsolidationRequired(_)) && row_numbers.len() > 1 =>
+            {
+                let mut first_half = row_numbers.iter().copied().zip(outputs).collect::<Vec<_>>();
+                let second_half = first_half.split_off(first_half.len() / 2);
+
+                log::info!("Rows {row_numbers:?} need too many inputs for one transaction, splitting them.");
+                chunks.push_front(second_half);
+                chunks.push_front(first_half);
+                continue;
+            }
+            Err(e) => Err(e.into()),
+        };
+
+        match transaction {
+            Ok(Some(transaction)) => {
+                for row_number in &row_numbers {
+                    report.rows.push(BatchRowReport {
+                        row: *row_number,
+                        row_hash: rows[row_number - 1].hash(),
+                        address: rows[row_number - 1].address.clone(),
+                        transaction_id: Some(transaction.transaction_id.to_string()),
+                        block_id: transaction.block_id.map(|block_id| block_id.to_string()),
+                        inclusion_state: Some(transaction.inclusion_state),
+                        error: None,
+                    });
+                }
+                report.save(&path).await?;
+
+                log::info!(
+                    "Batch transaction for rows {:?} sent:\n{:?}\n{:?}",
+                    row_numbers,
+                    transaction.transaction_id,
+                    transaction.block_id
+                );
+            }
+            Ok(None) => {
+                if !transaction_flags.dry_run {
+                    log::info!("Run the command again to resume the batch.");
+                }
+                return Ok(());
+            }
+            Err(e) => {
+                for row_number in &row_numbers {
+                    report.rows.push(BatchRowReport {
+                        row: *row_number,
+                        row_hash: rows[row_number - 1].hash(),
+                        address: rows[row_number - 1].address.clone(),
+                        error: Some(e.to_string()),
+                        ..Default::default()
+                    });
+                }
+                report.save(&path).await?;
+
+                return Err(Error::Miscellaneous(format!(
+                    "batch transaction for rows {row_numbers:?} failed, run the command again to resume: {e}"
+                )));
+            }
+        }
+    }
+
+    log::info!("Batch report written to \"{}\".", BatchReport::path(&path));
+
+    Ok(())
+}
+
+// `send-micro` command
+pub async fn send_micro_command(
+    account_handle: &AccountHandle,
+    address: String,
+    amount: u64,
+    transaction_flags: &TransactionFlags,
+) -> Result<(), Error> {
+    let outputs = vec![AddressWithMicroAmount {
+        address,
+        amount,
+        return_address: None,
+        expiration: None,
+    }];
+
+    let prepared_transaction = account_handle
+        .prepare_send_micro_transaction(outputs, Some(transaction_flags.transaction_options()?))
+        .await?;
+    let Some(transaction) =
+        sign_and_submit_transaction(account_handle, prepared_transaction, transaction_flags).await?
+    else {
+        return Ok(());
+    };
+
+    log::info!(
+        "Micro transaction sent:\n{:?}\n{:?}",
+        transaction.transaction_id,
+        transaction.block_id
+    );
+
+    Ok(())
+}
+
+// `send-native-token` command
+pub async fn send_native_token_command(
+    account_handle: &AccountHandle,
+    address: String,
+    token_id: String,
+    amount: String,
+    gift_storage_deposit: Option<bool>,
+    transaction_flags: &TransactionFlags,
+) -> Result<(), Error> {
+    let prepared_transaction = if gift_storage_deposit.unwrap_or(false) {
+        // Send native tokens together with the required storage deposit
+        let rent_structure = account_handle.client().get_rent_structure().await?;
+        let token_supply = account_handle.client().get_token_supply().await?;
+
+        let outputs = vec![
+            BasicOutputBuilder::new_with_minimum_storage_deposit(rent_structure)?
+                .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(
+                    Address::try_from_bech32(address)?.1,
+                )))
+                .with_native_tokens(vec![NativeToken::new(
+                    TokenId::from_str(&token_id)?,
+                    U256::from_dec_str(&amount).map_err(|e| Error::Miscellaneous(e.to_string()))?,
+                )?])
+                .finish_output(token_supply)?,
+        ];
+
+        account_handle
+            .prepare_transaction(outputs, Some(transaction_flags.transaction_options()?))
+            .await?
+    } else {
+        // Send native tokens with storage deposit return and expiration
+        let outputs = vec![AddressNativeTokens {
+            address,
+            native_tokens: vec![(
+                TokenId::from_str(&token_id)?,
+                U256::from_dec_str(&amount).map_err(|e| Error::Miscellaneous(e.to_string()))?,
+            )],
+            ..Default::default()
+        }];
+        account_handle
+            .prepare_send_native_tokens(outputs, Some(transaction_flags.transaction_options()?))
+            .await?
+    };
+
+    let Some(transaction) =
+        sign_and_submit_transaction(account_handle, prepared_transaction, transaction_flags).await?
+    else {
+        return Ok(());
+    };
+
+    log::info!(
+        "Native token transaction sent:\n{:?}\n{:?}",
+        transaction.transaction_id,
+        transaction.block_id
+    );
+
+    Ok(())
+}
+
+// `send-nft` command
+pub async fn send_nft_command(
+    account_handle: &AccountHandle,
+    address: String,
+    nft_id: String,
+    transaction_flags: &TransactionFlags,
+) -> Result<(), Error> {
+    let outputs = vec![AddressAndNftId {
+        address,
+        nft_id: NftId::from_str(&nft_id)?,
+    }];
+    let prepared_transaction = account_handle
+        .prepare_send_nft(outputs, Some(transaction_flags.transaction_options()?))
+        .await?;
+    let Some(transaction) =
+        sign_and_submit_transaction(account_handle, prepared_transaction, transaction_flags).await?
+    else {
+        return Ok(());
+    };
+
+    log::info!(
+        "Nft transaction sent:\n{:?}\n{:?}",
+        transaction.transaction_id,
+        transaction.block_id
+    );
+
+    Ok(())
+}
+
+// `send-output` command
+#[allow(clippy::too_many_arguments)]
+pub async fn send_output_command(
+    account_handle: &AccountHandle,
+    address: String,
+    amount: u64,
+    tag: Option<String>,
+    metadata: Option<Vec<u8>>,
+    sender: Option<String>,
+    expiration: Option<u32>,
+    timelock: Option<u32>,
+    storage_deposit_return: Option<u64>,
+    return_address: Option<String>,
+    tagged_data_tag: Option<String>,
+    tagged_data: Option<Vec<u8>>,
+    transaction_flags: &TransactionFlags,
+) -> Result<(), Error> {
+    let token_supply = account_handle.client().get_token_supply().await?;
+    let return_address = match return_address {
+        Some(return_address) => Address::try_from_bech32(return_address)?.1,
+        None => first_address(account_handle).await?,
+    };
+
+    let mut basic_output_builder = BasicOutputBuilder::new_with_amount(amount)?.add_unlock_condition(
+        UnlockCondition::Address(AddressUnlockCondition::new(Address::try_from_bech32(address)?.1)),
+    );
+
+    if let Some(tag) = tag {
+        basic_output_builder = basic_output_builder.add_feature(Feature::Tag(TagFeature::new(
+            prefix_hex::decode(&tag).map_err(|e| Error::Miscellaneous(e.to_string()))?,
+        )?));
+    }
+    if let Some(metadata) = metadata {
+        basic_output_builder = basic_output_builder.add_feature(Feature::Metadata(MetadataFeature::new(metadata)?));
+    }
+    if let Some(sender) = sender {
+        basic_output_builder =
+            basic_output_builder.add_feature(Feature::Sender(SenderFeature::new(Address::try_from_bech32(sender)?.1)));
+    }
+    if let Some(expiration) = expiration {
+        basic_output_builder = basic_output_builder.add_unlock_condition(UnlockCondition::Expiration(
+            ExpirationUnlockCondition::new(return_address, expiration)?,
+        ));
+    }
+    if let Some(timelock) = timelock {
+        basic_output_builder = basic_output_builder
+            .add_unlock_condition(UnlockCondition::Timelock(TimelockUnlockCondition::new(timelock)?));
+    }
+    if let Some(storage_deposit_return) = storage_deposit_return {
+        basic_output_builder = basic_output_builder.add_unlock_condition(UnlockCondition::StorageDepositReturn(
+            StorageDepositReturnUnlockCondition::new(return_address, storage_deposit_return, token_supply)?,
+        ));
+    }
+
+    let tagged_data_payload = if let Some(tagged_data) = tagged_data {
+        let tag = match tagged_data_tag {
+            Some(tag) => prefix_hex::decode(&tag).map_err(|e| Error::Miscellaneous(e.to_string()))?,
+            None => Vec::new(),
+        };
+        Some(TaggedDataPayload::new(tag, tagged_data)?)
+    } else {
+        None
+    };
+
+    let outputs = vec![basic_output_builder.finish_output(token_supply)?];
+    let prepared_transaction = account_handle
+        .prepare_transaction(
+            outputs,
+            Some(TransactionOptions {
+                tagged_data_payload,
+                ..transaction_flags.transaction_options()?
+            }),
+        )
+        .await?;
+    let Some(transaction) =
+        sign_and_submit_transaction(account_handle, prepared_transaction, transaction_flags).await?
+    else {
+        return Ok(());
+    };
+
+    log::info!(
+        "Transaction sent:\n{:?}\n{:?}",
+        transaction.transaction_id,
+        transaction.block_id
+    );
+
+    Ok(())
+}
+
+// `send-outputs` command
+pub async fn send_outputs_command(
+    account_handle: &AccountHandle,
+    path: String,
+    remainder_address: Option<String>,
+    transaction_flags: &TransactionFlags,
+) -> Result<(), Error> {
+    let token_supply = account_handle.client().get_token_supply().await?;
+    let rent_structure = account_handle.client().get_rent_structure().await?;
+    let outputs = read_json_file::<Vec<OutputDto>>(&path)
+        .await?
+        .iter()
+        .map(|output| Output::try_from_dto(output, token_supply))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if outputs.is_empty() || outputs.len() > OUTPUT_COUNT_MAX as usize {
+        return Err(Error::Miscellaneous(format!(
+            "{path} must hold between 1 and {OUTPUT_COUNT_MAX} outputs, not {}",
+            outputs.len()
+        )));
+    }
+    for (index, output) in outputs.iter().enumerate() {
+        output
+            .verify_storage_deposit(rent_structure, token_supply)
+            .map_err(|e| Error::Miscellaneous(format!("output {index} of {path} is invalid: {e}")))?;
+    }
+
+    let remainder_value_strategy = match remainder_address {
+        Some(remainder_address) => {
+            let address = Address::try_from_bech32(&remainder_address)?.1;
+            let account_address = account_handle
+                .addresses()
+                .await?
+                .into_iter()
+                .find(|account_address| *account_address.address().as_ref() == address)
+                .ok_or_else(|| Error::Miscellaneous(format!("{remainder_address} isn't an address of the account")))?;
+
+            RemainderValueStrategy::CustomAddress(account_address)
+        }
+        None => RemainderValueStrategy::default(),
+    };
+
+    let prepared_transaction = account_handle
+        .prepare_transaction(
+            outputs,
+            Some(TransactionOptions {
+                remainder_value_strategy,
+                ..transaction_flags.transaction_options()?
+            }),
+        )
+        .await?;
+    let Some(transaction) =
+        sign_and_submit_transaction(account_handle, prepared_transaction, transaction_flags).await?
+    else {
+        return Ok(());
+    };
+
+    log::info!(
+        "Transaction sent:\n{:?}\n{:?}",
+        transaction.transaction_id,
+        transaction.block_id
+    );
+
+    Ok(())
+}
+
+// `sweep` command
+pub async fn sweep_command(
+    account_handle: &AccountHandle,
+    address: String,
+    include_nfts: bool,
+    include_aliases: bool,
+    transaction_flags: &TransactionFlags,
+) -> Result<(), Error> {
+    log::info!("Sweeping account to {address}.");
+
+    let rent_structure = account_handle.client().get_rent_structure().await?;
+    let token_supply = account_handle.client().get_token_supply().await?;
+    let address = Address::try_from_bech32(address)?.1;
+    let locked_outputs = load_storage_file::<LockedOutputs>().await?;
+    let unspent_outputs = account_handle.unspent_outputs(None).await?;
+    let mut outputs = Vec::new();
+    // Swept NFTs and aliases are inputs too, their native tokens count towards the limit of a transaction.
+    let mut chain_inputs = Vec::new();
+    let mut chain_token_ids = HashSet::new();
+
+    // Base coins and native tokens held by NFTs and aliases stay with them.
+    for output_data in &unspent_outputs {
+        if locked_outputs.outputs.contains_key(&output_data.output_id.to_string()) {
+            continue;
+        }
 
-    Ok(())
-}
+        match &output_data.output {
+            Output::Nft(nft_output) if include_nfts => {
+                chain_inputs.push(output_data.output_id);
+                chain_token_ids.extend(
+                    nft_output
+                        .native_tokens()
+                        .iter()
+                        .map(|native_token| *native_token.token_id()),
+                );
+                outputs.push(
+                    NftOutputBuilder::from(nft_output)
+                        .with_nft_id(nft_output.nft_id_non_null(&output_data.output_id))
+                        .with_unlock_conditions(vec![UnlockCondition::Address(AddressUnlockCondition::new(address))])
+                        .finish_output(token_supply)?,
+                );
+            }
+            Output::Alias(alias_output) if include_aliases => {
+                chain_inputs.push(output_data.output_id);
+                chain_token_ids.extend(
+                    alias_output
+                        .native_tokens()
+                        .iter()
+                        .map(|native_token| *native_token.token_id()),
+                );
+                outputs.push(
+                    AliasOutputBuilder::from(alias_output)
+                        .with_alias_id(alias_output.alias_id_non_null(&output_data.output_id))
+                        .with_unlock_conditions(vec![
+                            UnlockCondition::StateControllerAddress(StateControllerAddressUnlockCondition::new(
+                                address,
+                            )),
+                            UnlockCondition::GovernorAddress(GovernorAddressUnlockCondition::new(address)),
+                        ])
+                        .finish_output(token_supply)?,
+                );
+            }
+            _ => {}
+        }
+    }
 
-/// `output` command
-pub async fn output_command(account_handle: &AccountHandle, output_id: String) -> Result<(), Error> {
-    let output = account_handle.get_output(&OutputId::from_str(&output_id)?).await;
+    let swept = sweep_basic_outputs(
+        unspent_outputs
+            .iter()
+            .map(|output_data| (&output_data.output_id, &output_data.output)),
+        &locked_outputs,
+        chain_inputs,
+        chain_token_ids,
+    );
 
-    if let Some(output) = output {
-        log::info!("{output:#?}");
-    } else {
-        log::info!("Output not found");
+    if swept.left_behind > 0 {
+        log::warn!(
+            "{} output(s) stay behind, a transaction has at most {} inputs and {} distinct native tokens. Run `sweep` \
+             again to sweep them.",
+            swept.left_behind,
+            INPUT_COUNT_MAX,
+            NativeTokens::COUNT_MAX
+        );
     }
 
-    Ok(())
-}
+    let amount = swept.amount;
+    let native_tokens = swept
+        .native_tokens
+        .into_iter()
+        .map(|(token_id, amount)| NativeToken::new(token_id, amount))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if amount > 0 {
+        let output = BasicOutputBuilder::new_with_amount(amount)?
+            .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(address)))
+            .with_native_tokens(native_tokens)
+            .finish_output(token_supply)?;
+
+        if output.amount() < output.rent_cost(&rent_structure) {
+            return Err(Error::Miscellaneous(format!(
+                "{amount} available base coins can't cover the storage deposit of {} required to sweep them",
+                output.rent_cost(&rent_structure)
+            )));
+        }
 
-/// `outputs` command
-pub async fn outputs_command(account_handle: &AccountHandle) -> Result<(), Error> {
-    let outputs = account_handle.outputs(None).await?;
+        outputs.push(output);
+    }
 
     if outputs.is_empty() {
-        log::info!("No outputs found");
-    } else {
-        let output_ids: Vec<OutputId> = outputs.iter().map(|o| o.output_id).collect();
-        log::info!("Outputs: {output_ids:#?}");
+        log::info!("Nothing to sweep.");
+        return Ok(());
     }
 
-    Ok(())
-}
-
-// `send` command
-pub async fn send_command(account_handle: &AccountHandle, address: String, amount: u64) -> Result<(), Error> {
-    let outputs = vec![AddressWithAmount { address, amount }];
-    let transaction = account_handle.send_amount(outputs, None).await?;
+    // Sweeping everything leaves no remainder, the inputs are chosen here so that locked outputs are left out.
+    let prepared_transaction = account_handle
+        .prepare_transaction(
+            outputs,
+            Some(TransactionOptions {
+                custom_inputs: Some(swept.inputs),
+                ..Default::default()
+            }),
+        )
+        .await?;
+    let Some(transaction) =
+        sign_and_submit_transaction(account_handle, prepared_transaction, transaction_flags).await?
+    else {
+        return Ok(());
+    };
 
     log::info!(
-        "Transaction sent:\n{:?}\n{:?}",
+        "Sweep transaction sent:\n{:?}\n{:?}",
         transaction.transaction_id,
         transaction.block_id
     );
@@ -486,133 +2554,419 @@ pub async fn send_command(account_handle: &AccountHandle, address: String, amoun
     Ok(())
 }
 
-// `send-micro` command
-pub async fn send_micro_command(account_handle: &AccountHandle, address: String, amount: u64) -> Result<(), Error> {
-    let outputs = vec![AddressWithMicroAmount {
-        address,
-        amount,
-        return_address: None,
-        expiration: None,
-    }];
+/// Inputs of a sweep and what they hold.
+#[derive(Debug, Default)]
+struct SweptOutputs {
+    inputs: Vec<OutputId>,
+    amount: u64,
+    native_tokens: BTreeMap<TokenId, U256>,
+    /// Basic outputs that didn't fit in the transaction.
+    left_behind: usize,
+}
 
-    let transaction = account_handle.send_micro_transaction(outputs, None).await?;
+/// Adds to the swept NFT and alias inputs the basic outputs that an address unlock condition alone locks and that
+/// aren't locked with `lock-output`, as long as the transaction stays within the input and native token limits.
+fn sweep_basic_outputs<'a>(
+    outputs: impl Iterator<Item = (&'a OutputId, &'a Output)>,
+    locked_outputs: &LockedOutputs,
+    inputs: Vec<OutputId>,
+    mut token_ids: HashSet<TokenId>,
+) -> SweptOutputs {
+    let mut swept = SweptOutputs {
+        inputs,
+        ..Default::default()
+    };
 
-    log::info!(
-        "Micro transaction sent:\n{:?}\n{:?}",
-        transaction.transaction_id,
-        transaction.block_id
-    );
+    for (output_id, output) in outputs {
+        let Output::Basic(basic_output) = output else {
+            continue;
+        };
+        if basic_output.unlock_conditions().len() != 1 || locked_outputs.outputs.contains_key(&output_id.to_string()) {
+            continue;
+        }
 
-    Ok(())
+        let output_token_ids = basic_output
+            .native_tokens()
+            .iter()
+            .map(|native_token| *native_token.token_id())
+            .collect::<HashSet<_>>();
+
+        if swept.inputs.len() == INPUT_COUNT_MAX as usize
+            || token_ids.union(&output_token_ids).count() > NativeTokens::COUNT_MAX as usize
+        {
+            swept.left_behind += 1;
+            continue;
+        }
+
+        swept.inputs.push(*output_id);
+        token_ids.extend(output_token_ids);
+        swept.amount += basic_output.amount();
+        for native_token in basic_output.native_tokens().iter() {
+            *swept.native_tokens.entry(*native_token.token_id()).or_default() += *native_token.amount();
+        }
+    }
+
+    swept
 }
 
-// `send-native-token` command
-pub async fn send_native_token_command(
+// `storage-deposit` command
+pub async fn storage_deposit_command(
     account_handle: &AccountHandle,
-    address: String,
-    token_id: String,
-    amount: String,
-    gift_storage_deposit: Option<bool>,
+    output: OutputDescription,
+    owned: bool,
 ) -> Result<(), Error> {
-    let transaction = if gift_storage_deposit.unwrap_or(false) {
-        // Send native tokens together with the required storage deposit
-        let rent_structure = account_handle.client().get_rent_structure().await?;
-        let token_supply = account_handle.client().get_token_supply().await?;
+    let rent_structure = account_handle.client().get_rent_structure().await?;
 
-        let outputs = vec![
-            BasicOutputBuilder::new_with_minimum_storage_deposit(rent_structure)?
-                .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(
-                    Address::try_from_bech32(address)?.1,
-                )))
-                .with_native_tokens(vec![NativeToken::new(
-                    TokenId::from_str(&token_id)?,
-                    U256::from_dec_str(&amount).map_err(|e| Error::Miscellaneous(e.to_string()))?,
-                )?])
-                .finish_output(token_supply)?,
-        ];
+    if owned {
+        let outputs = account_handle.unspent_outputs(None).await?;
+        let mut total = 0;
 
-        account_handle.send(outputs, None).await?
-    } else {
-        // Send native tokens with storage deposit return and expiration
-        let outputs = vec![AddressNativeTokens {
+        for output_data in &outputs {
+            let storage_deposit = output_data.output.rent_cost(&rent_structure);
+
+            total += storage_deposit;
+            log::info!(
+                "{} ({}): {storage_deposit} locked of {}",
+                output_data.output_id,
+                output_kind(&output_data.output),
+                output_data.output.amount()
+            );
+        }
+        log::info!("Storage deposit locked by {} output(s): {total}", outputs.len());
+
+        return Ok(());
+    }
+
+    let token_supply = account_handle.client().get_token_supply().await?;
+    let address = first_address(account_handle).await?;
+    // The sizes of the features and unlock conditions don't depend on their values, except for the data ones.
+    let return_amount = BasicOutputBuilder::new_with_minimum_storage_deposit(rent_structure)?
+        .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(address)))
+        .finish_output(token_supply)?
+        .amount();
+    let native_tokens = (0..output.native_tokens)
+        .map(|index| {
+            let mut token_id = [0u8; TokenId::LENGTH];
+            token_id[..8].copy_from_slice(&(index as u64).to_be_bytes());
+            NativeToken::new(TokenId::new(token_id), U256::one())
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut features = Vec::new();
+    let mut immutable_features = Vec::new();
+    let mut unlock_conditions = Vec::new();
+
+    if output.sender {
+        features.push(Feature::Sender(SenderFeature::new(address)));
+    }
+    if let Some(metadata) = bytes_from_hex_or_file(output.metadata_hex, output.metadata_file).await? {
+        features.push(Feature::Metadata(MetadataFeature::new(metadata)?));
+    }
+    if let Some(tag) = output.tag {
+        features.push(Feature::Tag(TagFeature::new(
+            prefix_hex::decode(&tag).map_err(|e| Error::Miscellaneous(e.to_string()))?,
+        )?));
+    }
+    if output.issuer {
+        immutable_features.push(Feature::Issuer(IssuerFeature::new(address)));
+    }
+    if let Some(immutable_metadata) =
+        bytes_from_hex_or_file(output.immutable_metadata_hex, output.immutable_metadata_file).await?
+    {
+        immutable_features.push(Feature::Metadata(MetadataFeature::new(immutable_metadata)?));
+    }
+    if output.storage_deposit_return {
+        unlock_conditions.push(UnlockCondition::StorageDepositReturn(
+            StorageDepositReturnUnlockCondition::new(address, return_amount, token_supply)?,
+        ));
+    }
+    if output.timelock {
+        unlock_conditions.push(UnlockCondition::Timelock(TimelockUnlockCondition::new(u32::MAX)?));
+    }
+    if output.expiration {
+        unlock_conditions.push(UnlockCondition::Expiration(ExpirationUnlockCondition::new(
             address,
-            native_tokens: vec![(
-                TokenId::from_str(&token_id)?,
-                U256::from_dec_str(&amount).map_err(|e| Error::Miscellaneous(e.to_string()))?,
-            )],
-            ..Default::default()
-        }];
-        account_handle.send_native_tokens(outputs, None).await?
+            u32::MAX,
+        )?));
+    }
+
+    let output = match output.kind.as_str() {
+        "basic" => {
+            if !immutable_features.is_empty() {
+                return Err(Error::Miscellaneous(
+                    "basic outputs can't have immutable features".to_string(),
+                ));
+            }
+            unlock_conditions.push(UnlockCondition::Address(AddressUnlockCondition::new(address)));
+            BasicOutputBuilder::new_with_minimum_storage_deposit(rent_structure)?
+                .with_native_tokens(native_tokens)
+                .with_features(features)
+                .with_unlock_conditions(unlock_conditions)
+                .finish_output(token_supply)?
+        }
+        "nft" => {
+            unlock_conditions.push(UnlockCondition::Address(AddressUnlockCondition::new(address)));
+            NftOutputBuilder::new_with_minimum_storage_deposit(rent_structure, NftId::null())?
+                .with_native_tokens(native_tokens)
+                .with_features(features)
+                .with_immutable_features(immutable_features)
+                .with_unlock_conditions(unlock_conditions)
+                .finish_output(token_supply)?
+        }
+        "alias" => {
+            unlock_conditions.push(UnlockCondition::StateControllerAddress(
+                StateControllerAddressUnlockCondition::new(address),
+            ));
+            unlock_conditions.push(UnlockCondition::GovernorAddress(GovernorAddressUnlockCondition::new(
+                address,
+            )));
+            AliasOutputBuilder::new_with_minimum_storage_deposit(rent_structure, AliasId::null())?
+                .with_native_tokens(native_tokens)
+                .with_features(features)
+                .with_immutable_features(immutable_features)
+                .with_unlock_conditions(unlock_conditions)
+                .finish_output(token_supply)?
+        }
+        "foundry" => {
+            unlock_conditions.push(UnlockCondition::ImmutableAliasAddress(
+                ImmutableAliasAddressUnlockCondition::new(AliasAddress::new(AliasId::null())),
+            ));
+            FoundryOutputBuilder::new_with_minimum_storage_deposit(
+                rent_structure,
+                1,
+                TokenScheme::Simple(SimpleTokenScheme::new(U256::zero(), U256::zero(), U256::one())?),
+            )?
+            .with_native_tokens(native_tokens)
+            .with_features(features)
+            .with_immutable_features(immutable_features)
+            .with_unlock_conditions(unlock_conditions)
+            .finish_output(token_supply)?
+        }
+        kind => {
+            return Err(Error::Miscellaneous(format!(
+                "unknown output type \"{kind}\", expected basic, alias, foundry or nft"
+            )));
+        }
     };
 
-    log::info!(
-        "Native token transaction sent:\n{:?}\n{:?}",
-        transaction.transaction_id,
-        transaction.block_id
-    );
+    log::info!("Minimum storage deposit: {}", output.amount());
+    if output
+        .unlock_conditions()
+        .and_then(|unlock_conditions| unlock_conditions.storage_deposit_return())
+        .is_some()
+    {
+        log::info!("Storage deposit returned to the sender: {return_amount}");
+    }
 
     Ok(())
 }
 
-// `send-nft` command
-pub async fn send_nft_command(account_handle: &AccountHandle, address: String, nft_id: String) -> Result<(), Error> {
-    let outputs = vec![AddressAndNftId {
-        address,
-        nft_id: NftId::from_str(&nft_id)?,
-    }];
-    let transaction = account_handle.send_nft(outputs, None).await?;
+// `submit-transaction` command
+pub async fn submit_transaction_command(
+    account_handle: &AccountHandle,
+    path: String,
+    transaction_flags: &TransactionFlags,
+) -> Result<(), Error> {
+    let protocol_parameters = account_handle.client().get_protocol_parameters().await?;
+    let signed_transaction = SignedTransactionData::try_from_dto(
+        &read_json_file::<SignedTransactionDataDto>(&path).await?,
+        &protocol_parameters,
+    )?;
+
+    let transaction = account_handle.submit_and_store_transaction(signed_transaction).await?;
 
     log::info!(
-        "Nft transaction sent:\n{:?}\n{:?}",
+        "Transaction sent:\n{:?}\n{:?}",
         transaction.transaction_id,
         transaction.block_id
     );
 
+    if transaction_flags.wait {
+        if let Some(block_id) = transaction.block_id {
+            wait_for_transaction(
+                account_handle,
+                &transaction.transaction_id,
+                &block_id,
+                transaction_flags.wait_timeout,
+            )
+            .await?;
+        }
+    }
+
     Ok(())
 }
 
 // `sync` command
-pub async fn sync_command(account_handle: &AccountHandle) -> Result<(), Error> {
+pub async fn sync_command(account_handle: &AccountHandle, transaction_flags: &TransactionFlags) -> Result<(), Error> {
     let sync = account_handle.sync(None).await?;
 
     log::info!("Synced: {sync:?}");
 
+    auto_claim(account_handle, transaction_flags).await
+}
+
+/// Claims the outputs expiring within the auto-claim window of the account, if it has one, after a sync.
+pub async fn auto_claim(account_handle: &AccountHandle, transaction_flags: &TransactionFlags) -> Result<(), Error> {
+    let account_index = *account_handle.read().await.index();
+    let claim_settings = load_storage_file::<ClaimSettings>().await?;
+
+    if let Some(window) = claim_settings.auto_claim_windows.get(&account_index).copied() {
+        let output_ids = outputs_to_claim(account_handle, OutputsToClaim::All).await?;
+        let output_ids = expiring_outputs(account_handle, output_ids, window).await;
+
+        if !output_ids.is_empty() {
+            log::info!(
+                "Claiming {} output(s) expiring within {}.",
+                output_ids.len(),
+                format_duration(window)
+            );
+            // Enabling auto-claim is the confirmation, so that syncing doesn't stop at the preview.
+            claim_outputs(
+                account_handle,
+                output_ids,
+                &TransactionFlags {
+                    yes: true,
+                    ..transaction_flags.clone()
+                },
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+// `transaction` command
+pub async fn transaction_command(account_handle: &AccountHandle, transaction_id: String) -> Result<(), Error> {
+    let transaction_id = TransactionId::from_str(&transaction_id)?;
+    let Some(transaction) = account_handle.get_transaction(&transaction_id).await else {
+        log::info!("Transaction not found");
+        return Ok(());
+    };
+    let account_addresses = account_addresses(account_handle).await?;
+    let summary = TransactionSummary::new(account_handle, &account_addresses, &transaction).await?;
+    let address_book = load_storage_file::<AddressBook>().await?;
+    let labels = load_storage_file::<Labels>().await?;
+    let bech32_hrp = account_handle.client().get_bech32_hrp().await?;
+    let mut log = format!(
+        "Transaction {transaction_id}\nDate: {}\nDirection: {}\nCounterparty: {}\nBase coin: {:+}",
+        summary.date(),
+        summary.direction,
+        describe_address(summary.counterparty, &bech32_hrp, &address_book),
+        summary.base_coin_delta
+    );
+
+    for (token_id, delta) in &summary.native_token_deltas {
+        log = format!("{log}\nNative token {token_id}: {delta}");
+    }
+    for nft_id in &summary.nfts_received {
+        log = format!("{log}\nNFT received: {nft_id}");
+    }
+    for nft_id in &summary.nfts_sent {
+        log = format!("{log}\nNFT sent: {nft_id}");
+    }
+    log = format!(
+        "{log}\nInclusion state: {:?}\nBlock: {}",
+        summary.inclusion_state,
+        summary
+            .block_id
+            .map_or_else(|| "-".to_string(), |block_id| block_id.to_string())
+    );
+    if let Some(note) = transaction_note(&labels, &transaction) {
+        log = format!("{log}\nNote: {note}");
+    }
+
+    log::info!(
+        "{log}\n{}",
+        serde_json::to_string_pretty(&TransactionDto::from(&transaction))?
+    );
+
     Ok(())
 }
 
 /// `transactions` command
-pub async fn transactions_command(account_handle: &AccountHandle) -> Result<(), Error> {
-    let transactions = account_handle.transactions().await?;
+pub async fn transactions_command(account_handle: &AccountHandle, filters: TransactionFilters) -> Result<(), Error> {
+    let transactions = filtered_transactions(account_handle, &filters).await?;
 
     if transactions.is_empty() {
         log::info!("No transactions found");
     } else {
-        for tx in transactions {
-            log::info!("{}", serde_json::to_string(&TransactionDto::from(&tx))?);
+        let address_book = load_storage_file::<AddressBook>().await?;
+        let labels = load_storage_file::<Labels>().await?;
+        let bech32_hrp = account_handle.client().get_bech32_hrp().await?;
+
+        for (transaction, summary) in transactions {
+            let mut log = summary.row(&describe_address(summary.counterparty, &bech32_hrp, &address_book));
+
+            if let Some(note) = transaction_note(&labels, &transaction) {
+                log = format!("{log}\n  Note: {note}");
+            }
+
+            log::info!("{log}");
         }
     }
 
     Ok(())
 }
 
-/// `unspent-outputs` command
-pub async fn unspent_outputs_command(account_handle: &AccountHandle) -> Result<(), Error> {
-    let outputs = account_handle.unspent_outputs(None).await?;
+// `unlock-output` command
+pub async fn unlock_output_command(output_id: String) -> Result<(), Error> {
+    let output_id = OutputId::from_str(&output_id)?;
+    let mut locked_outputs = load_storage_file::<LockedOutputs>().await?;
 
-    if outputs.is_empty() {
-        log::info!("No outputs found");
-    } else {
-        let output_ids: Vec<OutputId> = outputs.iter().map(|o| o.output_id).collect();
-        log::info!("Unspent outputs: {output_ids:#?}");
+    if locked_outputs.outputs.remove(&output_id.to_string()).is_none() {
+        return Err(Error::Miscellaneous(format!("output {output_id} isn't locked")));
     }
 
+    save_storage_file(&locked_outputs).await?;
+
+    log::info!("Output {output_id} unlocked.");
+
     Ok(())
 }
 
-pub async fn vote_command(account_handle: &AccountHandle, event_id: String, answers: Vec<u8>) -> Result<(), Error> {
+// `wait-transaction` command
+pub async fn wait_transaction_command(
+    account_handle: &AccountHandle,
+    transaction_id: String,
+    transaction_flags: &TransactionFlags,
+) -> Result<(), Error> {
+    let transaction_id = TransactionId::from_str(&transaction_id)?;
     let transaction = account_handle
-        .vote(Some(ParticipationEventId::from_str(&event_id)?), Some(answers))
+        .get_transaction(&transaction_id)
+        .await
+        .ok_or_else(|| Error::Miscellaneous(format!("transaction {transaction_id} not found in the account")))?;
+    let block_id = transaction
+        .block_id
+        .ok_or_else(|| Error::Miscellaneous(format!("transaction {transaction_id} has no block")))?;
+
+    wait_for_transaction(
+        account_handle,
+        &transaction_id,
+        &block_id,
+        transaction_flags.wait_timeout,
+    )
+    .await
+}
+
+/// `unspent-outputs` command
+pub async fn unspent_outputs_command(account_handle: &AccountHandle, filters: OutputFilters) -> Result<(), Error> {
+    print_outputs(account_handle, account_handle.unspent_outputs(None).await?, &filters).await
+}
+
+pub async fn vote_command(
+    account_handle: &AccountHandle,
+    event_id: String,
+    answers: Vec<u8>,
+    transaction_flags: &TransactionFlags,
+) -> Result<(), Error> {
+    let prepared_transaction = account_handle
+        .prepare_vote(Some(ParticipationEventId::from_str(&event_id)?), Some(answers))
         .await?;
+    let Some(transaction) =
+        sign_and_submit_transaction(account_handle, prepared_transaction, transaction_flags).await?
+    else {
+        return Ok(());
+    };
 
     log::info!(
         "Voting transaction sent:\n{:?}\n{:?}",
@@ -623,10 +2977,19 @@ pub async fn vote_command(account_handle: &AccountHandle, event_id: String, answ
     Ok(())
 }
 
-pub async fn stop_participating_command(account_handle: &AccountHandle, event_id: String) -> Result<(), Error> {
-    let transaction = account_handle
-        .stop_participating(ParticipationEventId::from_str(&event_id)?)
+pub async fn stop_participating_command(
+    account_handle: &AccountHandle,
+    event_id: String,
+    transaction_flags: &TransactionFlags,
+) -> Result<(), Error> {
+    let prepared_transaction = account_handle
+        .prepare_stop_participating(ParticipationEventId::from_str(&event_id)?)
         .await?;
+    let Some(transaction) =
+        sign_and_submit_transaction(account_handle, prepared_transaction, transaction_flags).await?
+    else {
+        return Ok(());
+    };
 
     log::info!(
         "Stop participating transaction sent:\n{:?}\n{:?}",
@@ -653,8 +3016,17 @@ pub async fn voting_power_command(account_handle: &AccountHandle) -> Result<(),
     Ok(())
 }
 
-pub async fn increase_voting_power_command(account_handle: &AccountHandle, amount: u64) -> Result<(), Error> {
-    let transaction = account_handle.increase_voting_power(amount).await?;
+pub async fn increase_voting_power_command(
+    account_handle: &AccountHandle,
+    amount: u64,
+    transaction_flags: &TransactionFlags,
+) -> Result<(), Error> {
+    let prepared_transaction = account_handle.prepare_increase_voting_power(amount).await?;
+    let Some(transaction) =
+        sign_and_submit_transaction(account_handle, prepared_transaction, transaction_flags).await?
+    else {
+        return Ok(());
+    };
 
     log::info!(
         "Increase voting power transaction sent:\n{:?}\n{:?}",
@@ -665,8 +3037,17 @@ pub async fn increase_voting_power_command(account_handle: &AccountHandle, amoun
     Ok(())
 }
 
-pub async fn decrease_voting_power_command(account_handle: &AccountHandle, amount: u64) -> Result<(), Error> {
-    let transaction = account_handle.decrease_voting_power(amount).await?;
+pub async fn decrease_voting_power_command(
+    account_handle: &AccountHandle,
+    amount: u64,
+    transaction_flags: &TransactionFlags,
+) -> Result<(), Error> {
+    let prepared_transaction = account_handle.prepare_decrease_voting_power(amount).await?;
+    let Some(transaction) =
+        sign_and_submit_transaction(account_handle, prepared_transaction, transaction_flags).await?
+    else {
+        return Ok(());
+    };
 
     log::info!(
         "Decrease voting power transaction sent:\n{:?}\n{:?}",
@@ -685,8 +3066,280 @@ pub async fn voting_output_command(account_handle: &AccountHandle) -> Result<(),
     Ok(())
 }
 
+async fn unspent_alias_output(account_handle: &AccountHandle, alias_id: &AliasId) -> Result<AliasOutput, Error> {
+    account_handle
+        .unspent_outputs(None)
+        .await?
+        .into_iter()
+        .find_map(|output_data| match output_data.output {
+            Output::Alias(alias_output) if alias_output.alias_id_non_null(&output_data.output_id) == *alias_id => {
+                Some(alias_output)
+            }
+            _ => None,
+        })
+        .ok_or_else(|| Error::Miscellaneous(format!("alias {alias_id} not found in the account")))
+}
+
+async fn prepare_alias_transition(
+    account_handle: &AccountHandle,
+    alias_output_builder: AliasOutputBuilder,
+    transaction_flags: &TransactionFlags,
+) -> Result<PreparedTransactionData, Error> {
+    let rent_structure = account_handle.client().get_rent_structure().await?;
+    let token_supply = account_handle.client().get_token_supply().await?;
+    let mut output = alias_output_builder.clone().finish_output(token_supply)?;
+
+    // Larger state metadata may require more storage deposit than the alias currently holds.
+    if output.amount() < output.rent_cost(&rent_structure) {
+        output = alias_output_builder
+            .with_minimum_storage_deposit(rent_structure)
+            .finish_output(token_supply)?;
+    }
+
+    Ok(account_handle
+        .prepare_transaction(vec![output], Some(transaction_flags.transaction_options()?))
+        .await?)
+}
+
+/// Parses a bech32 address, rejecting addresses of another network than the one with this HRP.
+fn parse_network_address(address: &str, bech32_hrp: &str) -> Result<Address, Error> {
+    let (hrp, parsed_address) = Address::try_from_bech32(address)?;
+
+    if hrp != bech32_hrp {
+        return Err(Error::Miscellaneous(format!(
+            "{address} is not a \"{bech32_hrp}\" address"
+        )));
+    }
+
+    Ok(parsed_address)
+}
+
+fn batch_row_output(
+    row: &BatchRow,
+    bech32_hrp: &str,
+    rent_structure: RentStructure,
+    token_supply: u64,
+    unspent_outputs: &[OutputData],
+) -> Result<Output, Error> {
+    let address = parse_network_address(&row.address, bech32_hrp)?;
+
+    let address_unlock_condition = UnlockCondition::Address(AddressUnlockCondition::new(address));
+
+    if let Some(nft_id) = &row.nft_id {
+        if row.amount.is_some() || row.token_id.is_some() {
+            return Err(Error::Miscellaneous(
+                "an NFT row can't also send an amount or a native token".to_string(),
+            ));
+        }
+
+        let nft_id = NftId::from_str(nft_id)?;
+        let nft_output = unspent_outputs
+            .iter()
+            .find_map(|output_data| match &output_data.output {
+                Output::Nft(nft_output) if nft_output.nft_id_non_null(&output_data.output_id) == nft_id => {
+                    Some(nft_output)
+                }
+                _ => None,
+            })
+            .ok_or_else(|| Error::Miscellaneous(format!("nft {nft_id} not found in the account")))?;
+
+        let output = NftOutputBuilder::from(nft_output)
+            .with_nft_id(nft_id)
+            .with_unlock_conditions(vec![address_unlock_condition])
+            .finish_output(token_supply)?;
+        output.verify_storage_deposit(rent_structure, token_supply)?;
+
+        return Ok(output);
+    }
+
+    let mut basic_output_builder = match row.amount {
+        Some(amount) => BasicOutputBuilder::new_with_amount(amount)?,
+        None => BasicOutputBuilder::new_with_minimum_storage_deposit(rent_structure)?,
+    }
+    .add_unlock_condition(address_unlock_condition);
+
+    match (&row.token_id, &row.token_amount) {
+        (Some(token_id), Some(token_amount)) => {
+            basic_output_builder = basic_output_builder.add_native_token(NativeToken::new(
+                TokenId::from_str(token_id)?,
+                U256::from_dec_str(token_amount).map_err(|e| Error::Miscellaneous(e.to_string()))?,
+            )?);
+        }
+        (None, None) if row.amount.is_none() => {
+            return Err(Error::Miscellaneous(
+                "a row needs an amount, a native token or an NFT".to_string(),
+            ));
+        }
+        (None, None) => {}
+        _ => {
+            return Err(Error::Miscellaneous(
+                "token_id and token_amount have to be given together".to_string(),
+            ));
+        }
+    }
+
+    let output = basic_output_builder.finish_output(token_supply)?;
+    output.verify_storage_deposit(rent_structure, token_supply)?;
+
+    Ok(output)
+}
+
+async fn first_address(account_handle: &AccountHandle) -> Result<Address, Error> {
+    match account_handle.addresses().await?.first() {
+        Some(address) => Ok(*address.address().as_ref()),
+        None => Err(Error::NoAddress),
+    }
+}
+
+/// Prints the outputs matching the filters as a table, oldest first.
+async fn print_outputs(
+    account_handle: &AccountHandle,
+    mut outputs: Vec<OutputData>,
+    filters: &OutputFilters,
+) -> Result<(), Error> {
+    let address = filters
+        .address
+        .as_deref()
+        .map(Address::try_from_bech32)
+        .transpose()?
+        .map(|(_, address)| address);
+    let expiring_before = filters
+        .expiring_before
+        .as_deref()
+        .map(|date| parse_date(date, false))
+        .transpose()?
+        .map(|timestamp| (timestamp / 1000) as u32);
+    let now = Utc::now().timestamp() as u32;
+
+    outputs.retain(|output_data| {
+        let output = &output_data.output;
+        let unlock_conditions = output.unlock_conditions();
+        let expiration = unlock_conditions.and_then(|unlock_conditions| unlock_conditions.expiration());
+        let locked = unlock_conditions.map_or(false, |unlock_conditions| {
+            unlock_conditions
+                .timelock()
+                .map_or(false, |timelock| timelock.timestamp() > now)
+                || unlock_conditions.expiration().is_some()
+                || unlock_conditions.storage_deposit_return().is_some()
+        });
+
+        filters.kind.as_ref().map_or(true, |kind| kind == output_kind(output))
+            && address.map_or(true, |address| output_data.address == address)
+            && (!filters.with_native_tokens
+                || output
+                    .native_tokens()
+                    .map_or(false, |native_tokens| !native_tokens.is_empty()))
+            && (!filters.locked || locked)
+            && expiring_before.map_or(true, |expiring_before| {
+                expiration.map_or(false, |expiration| expiration.timestamp() < expiring_before)
+            })
+            && filters
+                .min_amount
+                .map_or(true, |min_amount| output.amount() >= min_amount)
+    });
+
+    if outputs.is_empty() {
+        log::info!("No outputs found");
+        return Ok(());
+    }
+
+    outputs.sort_by_key(|output_data| output_data.metadata.milestone_timestamp_booked);
+
+    let bech32_hrp = account_handle.client().get_bech32_hrp().await?;
+
+    log::info!(
+        "{}",
+        outputs_table(
+            outputs
+                .iter()
+                .map(|output_data| (
+                    &output_data.output_id,
+                    &output_data.output,
+                    output_data.address.to_bech32(&bech32_hrp),
+                    output_data.metadata.milestone_timestamp_booked
+                ))
+                .collect()
+        )
+    );
+
+    Ok(())
+}
+
+/// Returns the transactions matching the filters, with their summary, oldest first.
+async fn filtered_transactions(
+    account_handle: &AccountHandle,
+    filters: &TransactionFilters,
+) -> Result<Vec<(Transaction, TransactionSummary)>, Error> {
+    let from = filters
+        .from
+        .as_deref()
+        .map(|date| parse_date(date, false))
+        .transpose()?;
+    let to = filters.to.as_deref().map(|date| parse_date(date, true)).transpose()?;
+    let state = filters
+        .state
+        .as_ref()
+        .map(|state| state.replace('-', "").to_lowercase());
+    let address = filters
+        .address
+        .as_deref()
+        .map(Address::try_from_bech32)
+        .transpose()?
+        .map(|(_, address)| address);
+    let token_id = filters.token.as_deref().map(TokenId::from_str).transpose()?;
+    let account_addresses = account_addresses(account_handle).await?;
+    let mut transactions = Vec::new();
+
+    for transaction in account_handle.transactions().await? {
+        let summary = TransactionSummary::new(account_handle, &account_addresses, &transaction).await?;
+
+        if from.map_or(true, |from| summary.timestamp >= from)
+            && to.map_or(true, |to| summary.timestamp <= to)
+            && state.as_ref().map_or(true, |state| {
+                *state == format!("{:?}", summary.inclusion_state).to_lowercase()
+            })
+            && address.map_or(true, |address| summary.addresses.contains(&address))
+            && token_id.map_or(true, |token_id| summary.native_token_deltas.contains_key(&token_id))
+        {
+            transactions.push((transaction, summary));
+        }
+    }
+
+    transactions.sort_by_key(|(_, summary)| summary.timestamp);
+
+    Ok(transactions)
+}
+
+/// Returns the note attached to a transaction, or the one given when sending it.
+fn transaction_note<'a>(labels: &'a Labels, transaction: &'a Transaction) -> Option<&'a String> {
+    labels
+        .transactions
+        .get(&transaction.transaction_id.to_string())
+        .or(transaction.note.as_ref())
+}
+
+/// Formats an optional address with its contact, if any.
+fn describe_address(address: Option<Address>, bech32_hrp: &str, address_book: &AddressBook) -> String {
+    address.map_or_else(
+        || "-".to_string(),
+        |address| format!("{}{}", address.to_bech32(bech32_hrp), address_book.describe(&address)),
+    )
+}
+
+fn print_contact(name: &str, contact: &Contact) {
+    match &contact.label {
+        Some(label) => log::info!("@{name}: {} ({label})", contact.address),
+        None => log::info!("@{name}: {}", contact.address),
+    }
+}
+
 async fn print_address(account_handle: &AccountHandle, address: &AccountAddress) -> Result<(), Error> {
-    let mut log = format!("Address {}: {}", address.key_index(), address.address().to_bech32());
+    let bech32_address = address.address().to_bech32();
+    let mut log = format!("Address {}: {bech32_address}", address.key_index());
+
+    if let Some(label) = load_storage_file::<Labels>().await?.addresses.get(&bech32_address) {
+        log = format!("{log}\nLabel: {label}");
+    }
 
     if *address.internal() {
         log = format!("{log}\nChange address");
@@ -704,3 +3357,65 @@ async fn print_address(account_handle: &AccountHandle, address: &AccountAddress)
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use iota_wallet::iota_client::block::address::Ed25519Address;
+
+    use super::*;
+
+    fn basic_output(amount: u64) -> Output {
+        BasicOutputBuilder::new_with_amount(amount)
+            .unwrap()
+            .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(Address::Ed25519(
+                Ed25519Address::new([0; 32]),
+            ))))
+            .finish_output(u64::MAX)
+            .unwrap()
+    }
+
+    fn output_id(index: u16) -> OutputId {
+        OutputId::new(TransactionId::new([1; 32]), index).unwrap()
+    }
+
+    #[test]
+    fn sweep_leaves_locked_outputs_out() {
+        let outputs = vec![
+            (output_id(0), basic_output(1_000_000)),
+            (output_id(1), basic_output(2_000_000)),
+            (output_id(2), basic_output(3_000_000)),
+        ];
+        let locked_outputs = LockedOutputs {
+            outputs: BTreeMap::from([(output_id(1).to_string(), "vesting".to_string())]),
+        };
+
+        let swept = sweep_basic_outputs(
+            outputs.iter().map(|(output_id, output)| (output_id, output)),
+            &locked_outputs,
+            Vec::new(),
+            HashSet::new(),
+        );
+
+        assert_eq!(swept.inputs, vec![output_id(0), output_id(2)]);
+        assert_eq!(swept.amount, 4_000_000);
+        assert_eq!(swept.left_behind, 0);
+    }
+
+    #[test]
+    fn sweep_input_limit() {
+        let outputs = (0..INPUT_COUNT_MAX + 2)
+            .map(|index| (output_id(index), basic_output(1_000_000)))
+            .collect::<Vec<_>>();
+
+        let swept = sweep_basic_outputs(
+            outputs.iter().map(|(output_id, output)| (output_id, output)),
+            &LockedOutputs::default(),
+            vec![output_id(INPUT_COUNT_MAX + 2)],
+            HashSet::new(),
+        );
+
+        assert_eq!(swept.inputs.len(), INPUT_COUNT_MAX as usize);
+        assert_eq!(swept.amount, (INPUT_COUNT_MAX as u64 - 1) * 1_000_000);
+        assert_eq!(swept.left_behind, 3);
+    }
+}
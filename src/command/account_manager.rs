@@ -11,7 +11,14 @@ use iota_wallet::{
 };
 use log::LevelFilter;
 
-use crate::{error::Error, helper::get_password};
+use crate::{
+    command::{
+        account::{auto_claim, TransactionFlags},
+        watch_only::WatchOnlyCommand,
+    },
+    error::Error,
+    helper::get_password,
+};
 
 #[derive(Debug, Clone, Parser)]
 #[clap(version, long_about = None)]
@@ -42,6 +49,9 @@ pub enum AccountManagerCommand {
     SetNode { url: String },
     /// Sync all accounts.
     Sync,
+    /// Manage watch-only accounts, usable without unlocking Stronghold.
+    #[clap(subcommand)]
+    WatchOnly(WatchOnlyCommand),
 }
 
 #[derive(Debug, Clone, Args)]
@@ -175,5 +185,9 @@ pub async fn sync_command(manager: &AccountManager) -> Result<(), Error> {
 
     log::info!("Synchronized all accounts: {:?}", total_balance);
 
+    for account_handle in manager.get_accounts().await? {
+        auto_claim(&account_handle, &TransactionFlags::default()).await?;
+    }
+
     Ok(())
 }
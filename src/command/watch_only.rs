@@ -0,0 +1,463 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    str::FromStr,
+};
+
+use clap::{Parser, Subcommand};
+use iota_wallet::{
+    account::types::InclusionState,
+    iota_client::{
+        api::PreparedTransactionDataDto,
+        block::{
+            address::Address,
+            input::{UtxoInput, INPUT_COUNT_MAX},
+            output::{unlock_condition::AddressUnlockCondition, BasicOutputBuilder, Output, OutputId, UnlockCondition},
+            payload::transaction::TransactionId,
+        },
+        node_api::indexer::query_parameters::QueryParameter,
+        Client,
+    },
+};
+
+use crate::{
+    balance::BalanceReport,
+    contacts::address_argument,
+    error::Error,
+    helper::{load_storage_file, save_storage_file, write_json_file},
+    history::{Direction, TransactionSummary},
+    output::outputs_table,
+    transaction::output_address,
+    watch_only::{watch_only_prompt, WatchOnlyAccount, WatchOnlyAccounts},
+};
+
+/// Manage the watch-only accounts, tracking addresses that aren't derived from the Stronghold mnemonic.
+#[derive(Debug, Clone, Subcommand)]
+pub enum WatchOnlyCommand {
+    /// Import addresses into a watch-only account, one bech32 address per line in `--file`: `watch-only import
+    /// treasury rms1qztwng6cty8cfm42nzvq099ev7udhrnk0rw8jt8vttf9kpqnxhpsx869vr3`
+    Import {
+        alias: String,
+        addresses: Vec<String>,
+        #[clap(short, long)]
+        file: Option<String>,
+    },
+    /// List the watch-only accounts.
+    List,
+    /// Create a watch-only account using the given node: `watch-only new treasury --node http://localhost:14265`
+    New {
+        alias: String,
+        #[clap(short, long)]
+        node: Option<String>,
+    },
+    /// Open the prompt of a watch-only account.
+    Open { alias: Option<String> },
+    /// Remove a watch-only account.
+    Remove { alias: String },
+}
+
+#[derive(Debug, Parser)]
+#[clap(version, long_about = None)]
+#[clap(propagate_version = true)]
+pub struct WatchOnlyCli {
+    #[clap(subcommand)]
+    pub command: WatchOnlyAccountCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum WatchOnlyAccountCommand {
+    /// List the watched addresses.
+    Addresses,
+    /// Print the balance of the watched addresses, as of the last sync.
+    Balance,
+    /// Exit from the watch-only account prompt.
+    Exit,
+    /// List the unspent outputs of the watched addresses, as of the last sync.
+    Outputs,
+    /// Prepare a transaction sending base coins from the watched addresses, to be signed with `sign-transaction` by
+    /// the account holding their keys: `prepare-send prepared.json
+    /// rms1qztwng6cty8cfm42nzvq099ev7udhrnk0rw8jt8vttf9kpqnxhpsx869vr3 1000000`
+    PrepareSend {
+        path: String,
+        #[clap(value_parser = address_argument)]
+        address: String,
+        amount: u64,
+    },
+    /// Fetch the outputs of the watched addresses from the node.
+    Sync,
+    /// List the transactions that created or spent outputs of the watched addresses.
+    Transactions,
+}
+
+pub async fn watch_only_command(command: WatchOnlyCommand) -> Result<(), Error> {
+    let mut accounts = load_storage_file::<WatchOnlyAccounts>().await?;
+
+    match command {
+        WatchOnlyCommand::Import { alias, addresses, file } => {
+            let mut addresses = addresses;
+
+            if let Some(file) = file {
+                addresses.extend(
+                    tokio::fs::read_to_string(file)
+                        .await?
+                        .lines()
+                        .map(str::trim)
+                        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                        .map(String::from),
+                );
+            }
+
+            let account = accounts.get_mut(&alias)?;
+            let bech32_hrp = Client::builder()
+                .with_node(&account.node_url)?
+                .finish()?
+                .get_bech32_hrp()
+                .await?;
+            let mut imported = 0;
+
+            for address in addresses {
+                // Validate the address before storing it, it has to be one of the network of the node.
+                if Address::try_from_bech32(&address)?.0 != bech32_hrp {
+                    return Err(Error::Miscellaneous(format!(
+                        "{address} is not a \"{bech32_hrp}\" address"
+                    )));
+                }
+
+                if !account.addresses.contains(&address) {
+                    account.addresses.push(address);
+                    imported += 1;
+                }
+            }
+
+            save_storage_file(&accounts).await?;
+
+            log::info!("Imported {imported} address(es) into watch-only account \"{alias}\".");
+        }
+        WatchOnlyCommand::List => {
+            if accounts.accounts.is_empty() {
+                log::info!("No watch-only accounts found");
+            }
+            for account in &accounts.accounts {
+                log::info!(
+                    "\"{}\": {} address(es), node {}",
+                    account.alias,
+                    account.addresses.len(),
+                    account.node_url
+                );
+            }
+        }
+        WatchOnlyCommand::New { alias, node } => {
+            if accounts.accounts.iter().any(|account| account.alias == alias) {
+                return Err(Error::Miscellaneous(format!(
+                    "watch-only account \"{alias}\" already exists"
+                )));
+            }
+
+            accounts.accounts.push(WatchOnlyAccount {
+                alias: alias.clone(),
+                node_url: node.unwrap_or_else(|| "http://localhost:14265".to_string()),
+                addresses: Vec::new(),
+                outputs: BTreeMap::new(),
+            });
+            save_storage_file(&accounts).await?;
+
+            log::info!("Created watch-only account \"{alias}\", import addresses with `watch-only import`.");
+        }
+        WatchOnlyCommand::Open { alias } => {
+            let alias = match alias {
+                Some(alias) => Some(alias),
+                None => accounts.pick()?,
+            };
+
+            if let Some(alias) = alias {
+                watch_only_prompt(accounts, alias).await?;
+            }
+        }
+        WatchOnlyCommand::Remove { alias } => {
+            accounts.get_mut(&alias)?;
+            accounts.accounts.retain(|account| account.alias != alias);
+            save_storage_file(&accounts).await?;
+
+            log::info!("Removed watch-only account \"{alias}\".");
+        }
+    }
+
+    Ok(())
+}
+
+// `addresses` command
+pub fn addresses_command(account: &WatchOnlyAccount) -> Result<(), Error> {
+    if account.addresses.is_empty() {
+        log::info!("No addresses found");
+    }
+    for address in &account.addresses {
+        log::info!("Address: {address}");
+    }
+
+    Ok(())
+}
+
+// `balance` command
+pub async fn balance_command(account: &WatchOnlyAccount) -> Result<(), Error> {
+    log::info!("{}", BalanceReport::watch_only(account).await?);
+
+    Ok(())
+}
+
+// `outputs` command
+pub fn outputs_command(account: &WatchOnlyAccount) -> Result<(), Error> {
+    let mut outputs = account.unspent_outputs()?;
+
+    if outputs.is_empty() {
+        log::info!("No outputs found");
+        return Ok(());
+    }
+
+    let bech32_hrp = watched_bech32_hrp(account)?;
+    let booked = |output_id: &OutputId| {
+        account.outputs[&output_id.to_string()]
+            .metadata
+            .milestone_timestamp_booked
+    };
+    outputs.sort_by_key(|(output_id, _)| booked(output_id));
+
+    log::info!(
+        "{}",
+        outputs_table(
+            outputs
+                .iter()
+                .map(|(output_id, output)| (
+                    output_id,
+                    output,
+                    output_address(output).map_or_else(|| "-".to_string(), |address| address.to_bech32(&bech32_hrp)),
+                    booked(output_id)
+                ))
+                .collect()
+        )
+    );
+
+    Ok(())
+}
+
+// `prepare-send` command
+pub async fn prepare_send_command(
+    account: &WatchOnlyAccount,
+    path: String,
+    address: String,
+    amount: u64,
+) -> Result<(), Error> {
+    let client = Client::builder().with_node(&account.node_url)?.finish()?;
+    let bech32_hrp = client.get_bech32_hrp().await?;
+    let rent_structure = client.get_rent_structure().await?;
+    let token_supply = client.get_token_supply().await?;
+    let Some(remainder_address) = account.addresses.first() else {
+        return Err(Error::NoAddress);
+    };
+    let (hrp, recipient) = Address::try_from_bech32(&address)?;
+
+    if hrp != bech32_hrp {
+        return Err(Error::Miscellaneous(format!(
+            "{address} is not a \"{bech32_hrp}\" address"
+        )));
+    }
+
+    let output = BasicOutputBuilder::new_with_amount(amount)?
+        .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(recipient)))
+        .finish_output(token_supply)?;
+    let remainder_storage_deposit = BasicOutputBuilder::new_with_minimum_storage_deposit(rent_structure)?
+        .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(
+            Address::try_from_bech32(remainder_address)?.1,
+        )))
+        .finish_output(token_supply)?
+        .amount();
+    let mut block_builder = client
+        .block()
+        .with_outputs(vec![output])?
+        .with_custom_remainder_address(remainder_address)?;
+    let mut input_amount = 0;
+    let mut input_count = 0;
+
+    // Only outputs without native tokens that an address unlock condition alone locks are selected, so that the
+    // remainder, if any, only holds base coins.
+    for (output_id, output) in account.unspent_outputs()? {
+        if input_amount == amount || input_amount >= amount + remainder_storage_deposit {
+            break;
+        }
+        if input_count == INPUT_COUNT_MAX {
+            return Err(Error::Miscellaneous(
+                "the amount needs too many inputs, consolidate the watched addresses first".to_string(),
+            ));
+        }
+
+        if let Output::Basic(basic_output) = &output {
+            if basic_output.unlock_conditions().len() == 1 && basic_output.native_tokens().is_empty() {
+                block_builder = block_builder.with_input(UtxoInput::from(output_id))?;
+                input_amount += basic_output.amount();
+                input_count += 1;
+            }
+        }
+    }
+
+    if input_amount != amount && input_amount < amount + remainder_storage_deposit {
+        return Err(Error::Miscellaneous(format!(
+            "only {input_amount} base coins are available in outputs without native tokens, {amount} are needed plus \
+             a storage deposit of {remainder_storage_deposit} for the remainder; sync the account if it's outdated"
+        )));
+    }
+
+    let prepared_transaction = block_builder.prepare_transaction().await?;
+    write_json_file(&path, &PreparedTransactionDataDto::from(&prepared_transaction)).await?;
+
+    log::info!("Prepared transaction written to \"{path}\", sign it with `sign-transaction`.");
+
+    Ok(())
+}
+
+// `sync` command
+pub async fn sync_command(account: &mut WatchOnlyAccount) -> Result<(), Error> {
+    let client = Client::builder().with_node(&account.node_url)?.finish()?;
+    let mut output_ids = BTreeSet::new();
+
+    for address in &account.addresses {
+        output_ids.extend(
+            client
+                .basic_output_ids(vec![QueryParameter::Address(address.clone())])
+                .await?,
+        );
+        output_ids.extend(
+            client
+                .nft_output_ids(vec![QueryParameter::Address(address.clone())])
+                .await?,
+        );
+        output_ids.extend(
+            client
+                .alias_output_ids(vec![QueryParameter::StateController(address.clone())])
+                .await?,
+        );
+        output_ids.extend(
+            client
+                .alias_output_ids(vec![QueryParameter::Governor(address.clone())])
+                .await?,
+        );
+    }
+
+    // Outputs that aren't unspent anymore are kept, with their spent metadata, to build the transaction history.
+    for (output_id, output_response) in account.outputs.iter_mut() {
+        if !output_response.metadata.is_spent && !output_ids.contains(&OutputId::from_str(output_id)?) {
+            output_response.metadata = client.get_output_metadata(&OutputId::from_str(output_id)?).await?;
+        }
+    }
+
+    let new_output_ids = output_ids
+        .into_iter()
+        .filter(|output_id| !account.outputs.contains_key(&output_id.to_string()))
+        .collect::<Vec<_>>();
+
+    for output_response in client.get_outputs(new_output_ids).await? {
+        let output_id = OutputId::new(
+            FromStr::from_str(&output_response.metadata.transaction_id)?,
+            output_response.metadata.output_index,
+        )?;
+        account.outputs.insert(output_id.to_string(), output_response);
+    }
+
+    log::info!(
+        "Synced: {} unspent output(s)",
+        account
+            .outputs
+            .values()
+            .filter(|output| !output.metadata.is_spent)
+            .count()
+    );
+
+    Ok(())
+}
+
+// `transactions` command
+pub fn transactions_command(account: &WatchOnlyAccount) -> Result<(), Error> {
+    let mut summaries = BTreeMap::<TransactionId, TransactionSummary>::new();
+
+    // Every output is received by the transaction creating it, and spent by the one consuming it, if any.
+    for (output_id, output_response) in &account.outputs {
+        let output_id = OutputId::from_str(output_id)?;
+        let output = Output::try_from_dto_unverified(&output_response.output)?;
+        let metadata = &output_response.metadata;
+        let mut events = vec![(*output_id.transaction_id(), metadata.milestone_timestamp_booked, true)];
+
+        if let (Some(transaction_id), Some(timestamp)) =
+            (&metadata.transaction_id_spent, metadata.milestone_timestamp_spent)
+        {
+            events.push((TransactionId::from_str(transaction_id)?, timestamp, false));
+        }
+
+        for (transaction_id, timestamp, received) in events {
+            let summary = summaries.entry(transaction_id).or_insert_with(|| TransactionSummary {
+                transaction_id,
+                timestamp: timestamp as u128 * 1000,
+                direction: Direction::Incoming,
+                counterparty: None,
+                base_coin_delta: 0,
+                native_token_deltas: BTreeMap::new(),
+                nfts_received: Vec::new(),
+                nfts_sent: Vec::new(),
+                storage_deposit_locked: 0,
+                storage_deposit_released: 0,
+                // The node only returns outputs of confirmed transactions.
+                inclusion_state: InclusionState::Confirmed,
+                block_id: None,
+                addresses: HashSet::new(),
+            });
+            summary.addresses.extend(output_address(&output));
+
+            if received {
+                summary.base_coin_delta += output.amount() as i128;
+            } else {
+                summary.direction = Direction::Outgoing;
+                summary.base_coin_delta -= output.amount() as i128;
+            }
+            if let Some(native_tokens) = output.native_tokens() {
+                for native_token in native_tokens.iter() {
+                    let delta = summary.native_token_deltas.entry(*native_token.token_id()).or_default();
+
+                    if received {
+                        delta.received += *native_token.amount();
+                    } else {
+                        delta.spent += *native_token.amount();
+                    }
+                }
+            }
+            if let Output::Nft(nft_output) = &output {
+                let nft_id = nft_output.nft_id_non_null(&output_id);
+
+                if received {
+                    summary.nfts_received.push(nft_id);
+                } else {
+                    summary.nfts_sent.push(nft_id);
+                }
+            }
+        }
+    }
+
+    if summaries.is_empty() {
+        log::info!("No transactions found");
+    } else {
+        let mut summaries = summaries.into_values().collect::<Vec<_>>();
+        summaries.sort_by_key(|summary| summary.timestamp);
+
+        for summary in summaries {
+            log::info!("{}", summary.row("-"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the bech32 HRP of the watched addresses.
+fn watched_bech32_hrp(account: &WatchOnlyAccount) -> Result<String, Error> {
+    match account.addresses.first() {
+        Some(address) => Ok(Address::try_from_bech32(address)?.0),
+        None => Err(Error::NoAddress),
+    }
+}
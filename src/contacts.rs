@@ -0,0 +1,92 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+use iota_wallet::iota_client::block::address::Address;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::Error,
+    helper::{storage_path, StorageFile},
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Contact {
+    /// Bech32 encoded address.
+    pub address: String,
+    pub label: Option<String>,
+}
+
+/// Contacts indexed by name.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AddressBook {
+    pub contacts: BTreeMap<String, Contact>,
+}
+
+impl StorageFile for AddressBook {
+    const FILE_NAME: &'static str = "contacts.json";
+}
+
+impl AddressBook {
+    pub fn get(&self, name: &str) -> Result<&Contact, Error> {
+        self.contacts
+            .get(name)
+            .ok_or_else(|| Error::Miscellaneous(format!("contact \"{name}\" not found")))
+    }
+
+    /// Replaces an `@name` argument by the address of the contact, other arguments are returned as is.
+    pub fn resolve(&self, argument: &str) -> Result<String, Error> {
+        match argument.strip_prefix('@') {
+            Some(name) => {
+                let contact = self.get(name)?;
+
+                log::info!("@{name} resolved to {}{}", contact.address, label_suffix(contact));
+
+                Ok(contact.address.clone())
+            }
+            None => Ok(argument.to_string()),
+        }
+    }
+
+    /// Returns the name of the contact with this address, if any.
+    pub fn find(&self, address: &Address) -> Option<(&String, &Contact)> {
+        self.contacts.iter().find(|(_, contact)| {
+            Address::try_from_bech32(&contact.address).map_or(false, |(_, contact_address)| contact_address == *address)
+        })
+    }
+
+    /// Describes the contact with this address, e.g. ` (@alice, exchange)`, or returns an empty string.
+    pub fn describe(&self, address: &Address) -> String {
+        self.find(address)
+            .map_or_else(String::new, |(name, contact)| match &contact.label {
+                Some(label) => format!(" (@{name}, {label})"),
+                None => format!(" (@{name})"),
+            })
+    }
+}
+
+/// Value parser of the address arguments, replacing an `@name` contact by its address.
+///
+/// Clap parses arguments synchronously, the address book is therefore read with blocking IO, only when a contact is
+/// given.
+pub fn address_argument(argument: &str) -> Result<String, String> {
+    if !argument.starts_with('@') {
+        return Ok(argument.to_string());
+    }
+
+    let address_book = match std::fs::read_to_string(PathBuf::from(storage_path()).join(AddressBook::FILE_NAME)) {
+        Ok(content) => serde_json::from_str(&content).map_err(|e| e.to_string())?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => AddressBook::default(),
+        Err(e) => return Err(e.to_string()),
+    };
+
+    address_book.resolve(argument).map_err(|e| e.to_string())
+}
+
+fn label_suffix(contact: &Contact) -> String {
+    contact
+        .label
+        .as_ref()
+        .map_or_else(String::new, |label| format!(" ({label})"))
+}
@@ -20,10 +20,14 @@ pub enum Error {
     Logger(#[from] LoggerError),
     #[error("{0}")]
     Miscellaneous(String),
+    #[error("generate at least one address before using this command")]
+    NoAddress,
     #[error("generate at least one address before using the faucet")]
     NoAddressForFaucet,
     #[error("serde_json error: {0}")]
     SerdeJson(#[from] SerdeJsonError),
+    #[error("watch-only accounts can't sign transactions")]
+    WatchOnly,
     #[error("wallet error: {0}")]
     Wallet(#[from] WalletError),
 }
@@ -1,11 +1,50 @@
 // Copyright 2020-2022 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+use std::{
+    env::var_os,
+    path::{Path, PathBuf},
+};
+
 use dialoguer::{console::Term, theme::ColorfulTheme, Password, Select};
 use iota_wallet::account_manager::AccountManager;
+use serde::{de::DeserializeOwned, Serialize};
 
 use crate::error::Error;
 
+/// Returns the path of the wallet database, `WALLET_DATABASE_PATH` or `./stardust-cli-wallet-db` by default.
+pub fn storage_path() -> String {
+    var_os("WALLET_DATABASE_PATH").map_or_else(
+        || "./stardust-cli-wallet-db".to_string(),
+        |os_str| os_str.into_string().expect("invalid WALLET_DATABASE_PATH"),
+    )
+}
+
+/// Returns the path of a file stored alongside the wallet database, in its directory.
+pub async fn storage_file_path(file_name: &str) -> Result<PathBuf, Error> {
+    let storage_path = storage_path();
+
+    tokio::fs::create_dir_all(&storage_path).await?;
+
+    Ok(PathBuf::from(storage_path).join(file_name))
+}
+
+/// CLI data the wallet storage has no place for, kept as a JSON file in the wallet database directory.
+///
+/// These files aren't part of the wallet storage, `backup` and `restore` don't include them.
+pub trait StorageFile: Default + Serialize + DeserializeOwned {
+    const FILE_NAME: &'static str;
+}
+
+/// Loads a storage file, or its default content if it doesn't exist yet.
+pub async fn load_storage_file<T: StorageFile>() -> Result<T, Error> {
+    read_json_file_or_default(storage_file_path(T::FILE_NAME).await?).await
+}
+
+pub async fn save_storage_file<T: StorageFile>(content: &T) -> Result<(), Error> {
+    write_json_file(storage_file_path(T::FILE_NAME).await?, content).await
+}
+
 pub fn get_password(prompt: &str, confirmation: bool) -> Result<String, Error> {
     let mut password = Password::new();
 
@@ -51,3 +90,22 @@ pub async fn bytes_from_hex_or_file(hex: Option<String>, file: Option<String>) -
         None
     })
 }
+
+pub async fn read_json_file<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<T, Error> {
+    Ok(serde_json::from_str(&tokio::fs::read_to_string(path).await?)?)
+}
+
+/// Reads a JSON file, or returns the default value if it doesn't exist yet.
+pub async fn read_json_file_or_default<T: DeserializeOwned + Default>(path: impl AsRef<Path>) -> Result<T, Error> {
+    match tokio::fs::read_to_string(path).await {
+        Ok(content) => Ok(serde_json::from_str(&content)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(T::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub async fn write_json_file<T: Serialize>(path: impl AsRef<Path>, value: &T) -> Result<(), Error> {
+    tokio::fs::write(path, serde_json::to_string_pretty(value)?).await?;
+
+    Ok(())
+}
@@ -0,0 +1,501 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+};
+
+use chrono::{NaiveDate, NaiveDateTime, TimeZone, Utc};
+use iota_wallet::{
+    account::{
+        types::{InclusionState, Transaction},
+        AccountHandle,
+    },
+    iota_client::block::{
+        address::Address,
+        input::Input,
+        output::{NftId, Output, OutputId, TokenId},
+        payload::transaction::{TransactionEssence, TransactionId},
+        BlockId,
+    },
+    U256,
+};
+use serde::Serialize;
+
+use crate::{balance::format_amount, error::Error, transaction::output_address};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Incoming,
+    Outgoing,
+    /// Every output is sent to an address of the account.
+    SelfTransfer,
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Incoming => write!(f, "incoming"),
+            Self::Outgoing => write!(f, "outgoing"),
+            Self::SelfTransfer => write!(f, "self"),
+        }
+    }
+}
+
+/// Change of the amount of a native token held by the account; `U256` being unsigned, both sides are kept.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NativeTokenDelta {
+    pub received: U256,
+    pub spent: U256,
+}
+
+impl NativeTokenDelta {
+    /// Formats the change in the unit having `decimals` decimals, e.g. `-2.5`.
+    pub fn in_unit(&self, decimals: u32) -> String {
+        if self.received >= self.spent {
+            format!("+{}", format_amount(self.received - self.spent, decimals))
+        } else {
+            format!("-{}", format_amount(self.spent - self.received, decimals))
+        }
+    }
+}
+
+impl fmt::Display for NativeTokenDelta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.received >= self.spent {
+            write!(f, "+{}", self.received - self.spent)
+        } else {
+            write!(f, "-{}", self.spent - self.received)
+        }
+    }
+}
+
+/// What a transaction changed for the account.
+#[derive(Debug, Clone)]
+pub struct TransactionSummary {
+    pub transaction_id: TransactionId,
+    /// Milliseconds since the Unix epoch.
+    pub timestamp: u128,
+    pub direction: Direction,
+    pub counterparty: Option<Address>,
+    pub base_coin_delta: i128,
+    pub native_token_deltas: BTreeMap<TokenId, NativeTokenDelta>,
+    pub nfts_received: Vec<NftId>,
+    pub nfts_sent: Vec<NftId>,
+    /// Storage deposits sent along outputs, to be returned to the account by their recipients.
+    pub storage_deposit_locked: u64,
+    /// Storage deposits returned to their senders when claiming outputs.
+    pub storage_deposit_released: u64,
+    pub inclusion_state: InclusionState,
+    pub block_id: Option<BlockId>,
+    /// Addresses of the known inputs and of the outputs.
+    pub addresses: HashSet<Address>,
+}
+
+impl TransactionSummary {
+    pub async fn new(
+        account_handle: &AccountHandle,
+        account_addresses: &HashSet<Address>,
+        transaction: &Transaction,
+    ) -> Result<Self, Error> {
+        let TransactionEssence::Regular(essence) = transaction.payload.essence();
+        let mut base_coin_delta = 0i128;
+        let mut native_token_deltas = BTreeMap::<TokenId, NativeTokenDelta>::new();
+        let mut addresses = HashSet::new();
+        let mut senders = Vec::new();
+        let mut nfts_received = Vec::new();
+        let mut nfts_sent = Vec::new();
+        let mut storage_deposit_locked = 0;
+        let mut storage_deposit_released = 0;
+
+        // Only the inputs of outgoing transactions are outputs of the account, other inputs are unknown.
+        for input in essence.inputs().iter() {
+            if let Input::Utxo(input) = input {
+                if let Some(output_data) = account_handle.get_output(input.output_id()).await {
+                    base_coin_delta -= output_data.output.amount() as i128;
+                    addresses.insert(output_data.address);
+
+                    if let Some(native_tokens) = output_data.output.native_tokens() {
+                        for native_token in native_tokens.iter() {
+                            native_token_deltas.entry(*native_token.token_id()).or_default().spent +=
+                                *native_token.amount();
+                        }
+                    }
+                    if let Output::Nft(nft_output) = &output_data.output {
+                        nfts_sent.push(nft_output.nft_id_non_null(input.output_id()));
+                    }
+                    if let Some(storage_deposit_return) = output_data
+                        .output
+                        .unlock_conditions()
+                        .and_then(|unlock_conditions| unlock_conditions.storage_deposit_return())
+                    {
+                        storage_deposit_released += storage_deposit_return.amount();
+                    }
+                }
+            }
+        }
+
+        let mut foreign_addresses = Vec::new();
+
+        for (index, output) in essence.outputs().iter().enumerate() {
+            let Some(address) = output_address(output) else {
+                continue;
+            };
+
+            addresses.insert(address);
+
+            if !account_addresses.contains(&address) {
+                foreign_addresses.push(address);
+
+                if let Some(storage_deposit_return) = output
+                    .unlock_conditions()
+                    .and_then(|unlock_conditions| unlock_conditions.storage_deposit_return())
+                {
+                    if account_addresses.contains(storage_deposit_return.return_address()) {
+                        storage_deposit_locked += storage_deposit_return.amount();
+                    }
+                }
+                continue;
+            }
+
+            base_coin_delta += output.amount() as i128;
+
+            if let Some(native_tokens) = output.native_tokens() {
+                for native_token in native_tokens.iter() {
+                    native_token_deltas
+                        .entry(*native_token.token_id())
+                        .or_default()
+                        .received += *native_token.amount();
+                }
+            }
+            if let Some(sender) = output.features().and_then(|features| features.sender()) {
+                senders.push(*sender.address());
+            }
+            if let Output::Nft(nft_output) = output {
+                let nft_id = nft_output.nft_id_non_null(&OutputId::new(transaction.transaction_id, index as u16)?);
+
+                // An NFT sent to the account itself doesn't change its holdings.
+                if let Some(position) = nfts_sent.iter().position(|sent_nft_id| *sent_nft_id == nft_id) {
+                    nfts_sent.remove(position);
+                } else {
+                    nfts_received.push(nft_id);
+                }
+            }
+        }
+
+        let direction = if transaction.incoming {
+            Direction::Incoming
+        } else if foreign_addresses.is_empty() {
+            Direction::SelfTransfer
+        } else {
+            Direction::Outgoing
+        };
+        // The sender of an incoming transaction is only known if it added a sender feature.
+        let counterparty = match direction {
+            Direction::Incoming => senders.first().copied(),
+            Direction::Outgoing => foreign_addresses.first().copied(),
+            Direction::SelfTransfer => None,
+        };
+
+        Ok(Self {
+            transaction_id: transaction.transaction_id,
+            timestamp: transaction.timestamp,
+            direction,
+            counterparty,
+            base_coin_delta,
+            native_token_deltas,
+            nfts_received,
+            nfts_sent,
+            storage_deposit_locked,
+            storage_deposit_released,
+            inclusion_state: transaction.inclusion_state,
+            block_id: transaction.block_id,
+            addresses,
+        })
+    }
+
+    /// Formats the timestamp as a UTC date and time.
+    pub fn date(&self) -> String {
+        NaiveDateTime::from_timestamp_millis(self.timestamp as i64).map_or_else(
+            || self.timestamp.to_string(),
+            |date| date.format("%Y-%m-%d %H:%M:%S").to_string(),
+        )
+    }
+
+    /// Formats the summary as a line of `transactions`.
+    pub fn row(&self, counterparty: &str) -> String {
+        let native_tokens = self
+            .native_token_deltas
+            .iter()
+            .map(|(token_id, delta)| format!("{delta} {token_id}"))
+            .collect::<Vec<_>>();
+
+        format!(
+            "{} | {} | {counterparty} | {:+} | {} | {:?} | {} | {}",
+            self.date(),
+            self.direction,
+            self.base_coin_delta,
+            if native_tokens.is_empty() {
+                "-".to_string()
+            } else {
+                native_tokens.join(", ")
+            },
+            self.inclusion_state,
+            self.block_id
+                .map_or_else(|| "-".to_string(), |block_id| block_id.to_string()),
+            self.transaction_id
+        )
+    }
+}
+
+/// A balance-changing event of a transaction, a row of `export-transactions`.
+#[derive(Debug, Serialize)]
+pub struct HistoryEvent {
+    pub date: String,
+    /// Milliseconds since the Unix epoch.
+    pub timestamp: u64,
+    pub transaction_id: String,
+    pub block_id: String,
+    pub inclusion_state: String,
+    pub direction: String,
+    /// Ticker of the base coin, native token id or NFT id.
+    pub asset: String,
+    /// Signed amount, in the smallest unit for base coins and native tokens.
+    pub amount: String,
+    /// Signed amount in the unit of the base coin or of the native token, empty for NFTs.
+    pub amount_in_unit: String,
+    pub storage_deposit_locked: u64,
+    pub storage_deposit_released: u64,
+    pub counterparty: String,
+    pub note: String,
+}
+
+impl TransactionSummary {
+    /// Splits the summary into one event per changed asset: base coins, then native tokens, then NFTs.
+    ///
+    /// A base coin event is kept when only storage deposits are locked or released. Native tokens without known
+    /// decimals are left in their smallest unit.
+    pub fn events(
+        &self,
+        ticker: &str,
+        decimals: u32,
+        token_decimals: &BTreeMap<TokenId, u32>,
+        counterparty: String,
+        note: Option<&String>,
+    ) -> Vec<HistoryEvent> {
+        let event = |asset: String, amount: String, amount_in_unit: String| HistoryEvent {
+            date: self.date(),
+            timestamp: self.timestamp as u64,
+            transaction_id: self.transaction_id.to_string(),
+            block_id: self.block_id.map_or_else(String::new, |block_id| block_id.to_string()),
+            inclusion_state: format!("{:?}", self.inclusion_state),
+            direction: self.direction.to_string(),
+            asset,
+            amount,
+            amount_in_unit,
+            storage_deposit_locked: 0,
+            storage_deposit_released: 0,
+            counterparty: counterparty.clone(),
+            note: note.cloned().unwrap_or_default(),
+        };
+        let mut events = Vec::new();
+
+        if self.base_coin_delta != 0 || self.storage_deposit_locked != 0 || self.storage_deposit_released != 0 {
+            events.push(HistoryEvent {
+                storage_deposit_locked: self.storage_deposit_locked,
+                storage_deposit_released: self.storage_deposit_released,
+                ..event(
+                    ticker.to_string(),
+                    format!("{:+}", self.base_coin_delta),
+                    format_in_unit(self.base_coin_delta, decimals),
+                )
+            });
+        }
+        for (token_id, delta) in &self.native_token_deltas {
+            if delta.received != delta.spent {
+                let decimals = token_decimals.get(token_id).copied().unwrap_or(0);
+                events.push(event(token_id.to_string(), delta.to_string(), delta.in_unit(decimals)));
+            }
+        }
+        for nft_id in &self.nfts_received {
+            events.push(event(nft_id.to_string(), "+1".to_string(), String::new()));
+        }
+        for nft_id in &self.nfts_sent {
+            events.push(event(nft_id.to_string(), "-1".to_string(), String::new()));
+        }
+
+        events
+    }
+}
+
+/// Formats an amount of the smallest unit in the unit having `decimals` decimals, e.g. `+1.5` for `1500000` and `6`.
+fn format_in_unit(amount: i128, decimals: u32) -> String {
+    let sign = if amount < 0 { '-' } else { '+' };
+
+    format!("{sign}{}", format_amount(U256::from(amount.unsigned_abs()), decimals))
+}
+
+/// Writes events as CSV, with a header line.
+pub fn events_to_csv(events: &[HistoryEvent]) -> String {
+    let mut csv = String::from(
+        "date,timestamp,transaction_id,block_id,inclusion_state,direction,asset,amount,amount_in_unit,\
+         storage_deposit_locked,storage_deposit_released,counterparty,note\n",
+    );
+
+    for event in events {
+        let fields = [
+            event.date.clone(),
+            event.timestamp.to_string(),
+            event.transaction_id.clone(),
+            event.block_id.clone(),
+            event.inclusion_state.clone(),
+            event.direction.clone(),
+            event.asset.clone(),
+            event.amount.clone(),
+            event.amount_in_unit.clone(),
+            event.storage_deposit_locked.to_string(),
+            event.storage_deposit_released.to_string(),
+            event.counterparty.clone(),
+            event.note.clone(),
+        ];
+
+        csv.push_str(
+            &fields
+                .iter()
+                .map(|field| csv_field(field))
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+        csv.push('\n');
+    }
+
+    csv
+}
+
+/// Quotes a CSV field if it contains a separator, a quote or a line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Parses a `YYYY-MM-DD` date, at the start of the day or at its end, into milliseconds since the Unix epoch.
+///
+/// Dates before the epoch are rejected, no transaction can be that old.
+pub fn parse_date(date: &str, end_of_day: bool) -> Result<u128, Error> {
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|e| Error::Miscellaneous(format!("invalid date \"{date}\": {e}")))?;
+    let date_time = if end_of_day {
+        date.and_hms_milli_opt(23, 59, 59, 999)
+    } else {
+        date.and_hms_opt(0, 0, 0)
+    }
+    .ok_or_else(|| Error::Miscellaneous(format!("invalid date \"{date}\"")))?;
+
+    u128::try_from(Utc.from_utc_datetime(&date_time).timestamp_millis())
+        .map_err(|_| Error::Miscellaneous(format!("invalid date \"{date}\", dates start in 1970")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(note: &str) -> HistoryEvent {
+        HistoryEvent {
+            date: "2023-01-01 00:00:00".to_string(),
+            timestamp: 1672531200000,
+            transaction_id: "0x01".to_string(),
+            block_id: String::new(),
+            inclusion_state: "Confirmed".to_string(),
+            direction: "incoming".to_string(),
+            asset: "SMR".to_string(),
+            amount: "+1000000".to_string(),
+            amount_in_unit: "+1".to_string(),
+            storage_deposit_locked: 0,
+            storage_deposit_released: 0,
+            counterparty: "-".to_string(),
+            note: note.to_string(),
+        }
+    }
+
+    #[test]
+    fn format_in_unit_decimals() {
+        assert_eq!(format_in_unit(1_500_000, 6), "+1.5");
+        assert_eq!(format_in_unit(-1_000_000, 6), "-1");
+        assert_eq!(format_in_unit(1, 6), "+0.000001");
+        assert_eq!(format_in_unit(0, 6), "+0");
+        assert_eq!(format_in_unit(42, 0), "+42");
+    }
+
+    #[test]
+    fn format_in_unit_absurd_decimals() {
+        assert_eq!(format_in_unit(-1_500_000, 39), format!("-0.{}15", "0".repeat(32)));
+        assert_eq!(format_in_unit(1_500_000, 78), "+1500000");
+        assert_eq!(format_in_unit(i128::MIN, 0), format!("-{}", i128::MIN.unsigned_abs()));
+    }
+
+    #[test]
+    fn native_token_delta_in_unit() {
+        let received = NativeTokenDelta {
+            received: U256::from(2_500u64),
+            spent: U256::zero(),
+        };
+        let spent = NativeTokenDelta {
+            received: U256::from(500u64),
+            spent: U256::from(3_000u64),
+        };
+
+        assert_eq!(received.in_unit(3), "+2.5");
+        assert_eq!(spent.in_unit(3), "-2.5");
+        assert_eq!(spent.in_unit(0), "-2500");
+    }
+
+    #[test]
+    fn csv_field_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field(""), "");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+        assert_eq!(csv_field("carriage\rreturn"), "\"carriage\rreturn\"");
+    }
+
+    #[test]
+    fn events_to_csv_rows() {
+        let csv = events_to_csv(&[event("rent"), event("rent, \"march\"")]);
+        let lines = csv.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("date,timestamp,transaction_id,"));
+        assert_eq!(lines[0].split(',').count(), 13);
+        assert_eq!(
+            lines[1],
+            "2023-01-01 00:00:00,1672531200000,0x01,,Confirmed,incoming,SMR,+1000000,+1,0,0,-,rent"
+        );
+        assert!(lines[2].ends_with(",-,\"rent, \"\"march\"\"\""));
+    }
+
+    #[test]
+    fn events_to_csv_empty() {
+        assert_eq!(events_to_csv(&[]).lines().count(), 1);
+    }
+
+    #[test]
+    fn parse_date_bounds() {
+        assert_eq!(parse_date("2023-01-01", false).unwrap(), 1672531200000);
+        assert_eq!(parse_date("2023-01-01", true).unwrap(), 1672617599999);
+    }
+
+    #[test]
+    fn parse_date_invalid() {
+        assert!(parse_date("2023-13-01", false).is_err());
+        assert!(parse_date("2023-02-30", false).is_err());
+        assert!(parse_date("01/01/2023", false).is_err());
+        assert!(parse_date("", true).is_err());
+        assert!(parse_date("1969-12-31", false).is_err());
+        assert!(parse_date("1969-12-31", true).is_err());
+    }
+}
@@ -0,0 +1,34 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::helper::StorageFile;
+
+/// Labels of own addresses and notes of transactions.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Labels {
+    /// Labels indexed by bech32 encoded address.
+    #[serde(default)]
+    pub addresses: BTreeMap<String, String>,
+    /// Notes indexed by transaction id.
+    #[serde(default)]
+    pub transactions: BTreeMap<String, String>,
+}
+
+impl StorageFile for Labels {
+    const FILE_NAME: &'static str = "labels.json";
+}
+
+/// Sets the value of a key, or removes it if the value is empty.
+pub fn set_or_remove(map: &mut BTreeMap<String, String>, key: String, value: Vec<String>) {
+    let value = value.join(" ");
+
+    if value.is_empty() {
+        map.remove(&key);
+    } else {
+        map.insert(key, value);
+    }
+}
@@ -0,0 +1,20 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::helper::StorageFile;
+
+/// Outputs kept out of automatic input selection.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LockedOutputs {
+    /// Notes indexed by output id, empty when none was given.
+    #[serde(default)]
+    pub outputs: BTreeMap<String, String>,
+}
+
+impl StorageFile for LockedOutputs {
+    const FILE_NAME: &'static str = "locked-outputs.json";
+}
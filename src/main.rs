@@ -3,9 +3,19 @@
 
 mod account;
 mod account_manager;
+mod balance;
+mod batch;
+mod claims;
 mod command;
+mod contacts;
 mod error;
 mod helper;
+mod history;
+mod labels;
+mod locks;
+mod output;
+mod transaction;
+mod watch_only;
 
 use clap::Parser;
 use fern_logger::{LoggerConfigBuilder, LoggerOutputConfigBuilder};
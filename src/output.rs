@@ -0,0 +1,282 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashSet;
+
+use chrono::{NaiveDateTime, Utc};
+use iota_wallet::iota_client::block::{
+    address::Address,
+    output::{
+        feature::Features, unlock_condition::UnlockConditions, Feature, Output, OutputId, Rent, RentStructure,
+        TokenScheme, UnlockCondition,
+    },
+};
+
+use crate::{contacts::AddressBook, transaction::output_kind};
+
+/// Context needed to describe outputs: the network, the addresses of the account and the contacts.
+pub struct OutputContext<'a> {
+    pub bech32_hrp: &'a str,
+    pub rent_structure: RentStructure,
+    pub account_addresses: &'a HashSet<Address>,
+    pub address_book: &'a AddressBook,
+}
+
+impl OutputContext<'_> {
+    /// Formats an address, marking the ones of the account and the contacts.
+    pub fn address(&self, address: &Address) -> String {
+        format!(
+            "{}{}{}",
+            address.to_bech32(self.bech32_hrp),
+            if self.account_addresses.contains(address) {
+                " (own address)"
+            } else {
+                ""
+            },
+            self.address_book.describe(address)
+        )
+    }
+}
+
+/// Decodes an output for humans: type, amount, native tokens, unlock conditions, features and storage deposit.
+pub fn describe_output(output_id: &OutputId, output: &Output, context: &OutputContext<'_>) -> String {
+    let now = Utc::now().timestamp() as u32;
+    let mut description = format!(
+        "Output {output_id}\nType: {}\nAmount: {}\nStorage deposit: {}",
+        output_kind(output),
+        output.amount(),
+        output.rent_cost(&context.rent_structure)
+    );
+
+    match output {
+        Output::Alias(alias_output) => {
+            description.push_str(&format!(
+                "\nAlias id: {}\nState index: {}\nFoundry counter: {}",
+                alias_output.alias_id_non_null(output_id),
+                alias_output.state_index(),
+                alias_output.foundry_counter()
+            ));
+            if !alias_output.state_metadata().is_empty() {
+                description.push_str(&format!(
+                    "\nState metadata: {}",
+                    decode_bytes(alias_output.state_metadata())
+                ));
+            }
+        }
+        Output::Foundry(foundry_output) => {
+            description.push_str(&format!(
+                "\nFoundry id: {}\nSerial number: {}",
+                foundry_output.id(),
+                foundry_output.serial_number()
+            ));
+            let TokenScheme::Simple(token_scheme) = foundry_output.token_scheme();
+            description.push_str(&format!(
+                "\nMinted tokens: {}\nMelted tokens: {}\nMaximum supply: {}",
+                token_scheme.minted_tokens(),
+                token_scheme.melted_tokens(),
+                token_scheme.maximum_supply()
+            ));
+        }
+        Output::Nft(nft_output) => {
+            description.push_str(&format!("\nNFT id: {}", nft_output.nft_id_non_null(output_id)));
+        }
+        Output::Basic(_) | Output::Treasury(_) => {}
+    }
+
+    if let Some(native_tokens) = output.native_tokens().filter(|native_tokens| !native_tokens.is_empty()) {
+        description.push_str("\nNative tokens:");
+        for native_token in native_tokens.iter() {
+            description.push_str(&format!("\n  {}: {}", native_token.token_id(), native_token.amount()));
+        }
+    }
+
+    if let Some(unlock_conditions) = output.unlock_conditions() {
+        description.push_str("\nUnlock conditions:");
+        for unlock_condition in unlock_conditions.iter() {
+            description.push_str(&format!(
+                "\n  {}",
+                describe_unlock_condition(unlock_condition, now, context)
+            ));
+        }
+    }
+
+    if let Some(features) = output.features() {
+        describe_features("Features", features, context, &mut description);
+    }
+    if let Some(immutable_features) = output.immutable_features() {
+        describe_features("Immutable features", immutable_features, context, &mut description);
+    }
+
+    description
+}
+
+/// Describes an output awaiting claim: sender, amount, native tokens or NFT, return deposit and expiration deadline.
+pub fn describe_claimable_output(output_id: &OutputId, output: &Output, context: &OutputContext<'_>) -> String {
+    let mut description = format!(
+        "Output {output_id}\nType: {}\nAmount: {}",
+        output_kind(output),
+        output.amount()
+    );
+
+    if let Some(sender) = output.features().and_then(|features| features.sender()) {
+        description.push_str(&format!("\nSender: {}", context.address(sender.address())));
+    }
+    if let Output::Nft(nft_output) = output {
+        description.push_str(&format!("\nNFT id: {}", nft_output.nft_id_non_null(output_id)));
+    }
+    if let Some(native_tokens) = output.native_tokens() {
+        for native_token in native_tokens.iter() {
+            description.push_str(&format!(
+                "\nNative token {}: {}",
+                native_token.token_id(),
+                native_token.amount()
+            ));
+        }
+    }
+    if let Some(unlock_conditions) = output.unlock_conditions() {
+        if let Some(storage_deposit_return) = unlock_conditions.storage_deposit_return() {
+            description.push_str(&format!(
+                "\nReturn deposit: {} to {}",
+                storage_deposit_return.amount(),
+                context.address(storage_deposit_return.return_address())
+            ));
+        }
+        if let Some(expiration) = unlock_conditions.expiration() {
+            description.push_str(&format!(
+                "\nExpiration deadline: {}",
+                format_timestamp(expiration.timestamp())
+            ));
+        }
+        if let Some(timelock) = unlock_conditions.timelock() {
+            description.push_str(&format!(
+                "\nTimelocked until: {}",
+                format_timestamp(timelock.timestamp())
+            ));
+        }
+    }
+
+    description
+}
+
+fn describe_unlock_condition(unlock_condition: &UnlockCondition, now: u32, context: &OutputContext<'_>) -> String {
+    match unlock_condition {
+        UnlockCondition::Address(condition) => format!("Address: {}", context.address(condition.address())),
+        UnlockCondition::StorageDepositReturn(condition) => format!(
+            "Storage deposit return: {} to {}",
+            condition.amount(),
+            context.address(condition.return_address())
+        ),
+        UnlockCondition::Timelock(condition) => format!(
+            "Timelock: until {} ({})",
+            format_timestamp(condition.timestamp()),
+            if now >= condition.timestamp() {
+                "unlocked"
+            } else {
+                "locked"
+            }
+        ),
+        UnlockCondition::Expiration(condition) => format!(
+            "Expiration: at {} to {} ({})",
+            format_timestamp(condition.timestamp()),
+            context.address(condition.return_address()),
+            if now >= condition.timestamp() {
+                "expired, only the return address can unlock it"
+            } else {
+                "not expired"
+            }
+        ),
+        UnlockCondition::StateControllerAddress(condition) => {
+            format!("State controller: {}", context.address(condition.address()))
+        }
+        UnlockCondition::GovernorAddress(condition) => format!("Governor: {}", context.address(condition.address())),
+        UnlockCondition::ImmutableAliasAddress(condition) => {
+            format!("Immutable alias: {}", context.address(condition.address()))
+        }
+    }
+}
+
+fn describe_features(title: &str, features: &Features, context: &OutputContext<'_>, description: &mut String) {
+    if features.is_empty() {
+        return;
+    }
+
+    description.push_str(&format!("\n{title}:"));
+
+    for feature in features.iter() {
+        description.push_str(&format!(
+            "\n  {}",
+            match feature {
+                Feature::Sender(sender) => format!("Sender: {}", context.address(sender.address())),
+                Feature::Issuer(issuer) => format!("Issuer: {}", context.address(issuer.address())),
+                Feature::Metadata(metadata) => format!("Metadata: {}", decode_bytes(metadata.data())),
+                Feature::Tag(tag) => format!("Tag: {}", decode_bytes(tag.tag())),
+            }
+        ));
+    }
+}
+
+/// Short summary of the unlock conditions of an output, e.g. `address, timelock 2023-01-01 00:00:00`.
+pub fn unlock_conditions_summary(unlock_conditions: &UnlockConditions) -> String {
+    unlock_conditions
+        .iter()
+        .map(|unlock_condition| match unlock_condition {
+            UnlockCondition::Address(_) => "address".to_string(),
+            UnlockCondition::StorageDepositReturn(condition) => {
+                format!("storage deposit return {}", condition.amount())
+            }
+            UnlockCondition::Timelock(condition) => format!("timelock {}", format_timestamp(condition.timestamp())),
+            UnlockCondition::Expiration(condition) => {
+                format!("expiration {}", format_timestamp(condition.timestamp()))
+            }
+            UnlockCondition::StateControllerAddress(_) => "state controller".to_string(),
+            UnlockCondition::GovernorAddress(_) => "governor".to_string(),
+            UnlockCondition::ImmutableAliasAddress(_) => "immutable alias".to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Formats outputs as the table of `outputs`, given their id, address and booking timestamp.
+pub fn outputs_table(outputs: Vec<(&OutputId, &Output, String, u32)>) -> String {
+    let mut table = format!(
+        "{} output(s)\n{:<70}  {:<7}  {:>20}  {:<64}  {:<19}  Unlock conditions",
+        outputs.len(),
+        "Output id",
+        "Type",
+        "Amount",
+        "Address",
+        "Booked"
+    );
+
+    for (output_id, output, address, booked) in outputs {
+        table.push_str(&format!(
+            "\n{:<70}  {:<7}  {:>20}  {:<64}  {:<19}  {}",
+            output_id.to_string(),
+            output_kind(output),
+            output.amount(),
+            address,
+            format_timestamp(booked),
+            output
+                .unlock_conditions()
+                .map_or_else(String::new, unlock_conditions_summary)
+        ));
+    }
+
+    table
+}
+
+/// Formats seconds since the Unix epoch as a UTC date and time.
+pub fn format_timestamp(timestamp: u32) -> String {
+    NaiveDateTime::from_timestamp_opt(timestamp as i64, 0).map_or_else(
+        || timestamp.to_string(),
+        |date| date.format("%Y-%m-%d %H:%M:%S").to_string(),
+    )
+}
+
+/// Decodes bytes as UTF-8 when they are printable, as hex otherwise.
+fn decode_bytes(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) if !text.chars().any(char::is_control) => format!("\"{text}\""),
+        _ => prefix_hex::encode(bytes),
+    }
+}
@@ -0,0 +1,389 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
+
+use dialoguer::Confirm;
+use iota_wallet::{
+    account::{types::Transaction, AccountHandle},
+    iota_client::{
+        api::{PreparedTransactionData, PreparedTransactionDataDto},
+        api_types::core::response::LedgerInclusionStateDto,
+        block::{
+            address::Address,
+            output::{Output, Rent},
+            payload::transaction::{TransactionEssence, TransactionId},
+            semantic::ConflictReason,
+            BlockId,
+        },
+        constants::HD_WALLET_TYPE,
+        crypto::keys::slip10::Chain,
+    },
+};
+
+use crate::{
+    command::account::TransactionFlags,
+    contacts::AddressBook,
+    error::Error,
+    helper::{load_storage_file, write_json_file},
+    locks::LockedOutputs,
+};
+
+/// Shows a preview of a prepared transaction and, once confirmed, signs and submits it.
+///
+/// Returns `None` if the transaction has been discarded, if it is a dry run or if it has been written to a file to be
+/// signed offline.
+pub async fn sign_and_submit_transaction(
+    account_handle: &AccountHandle,
+    prepared_transaction: PreparedTransactionData,
+    transaction_flags: &TransactionFlags,
+) -> Result<Option<Transaction>, Error> {
+    check_inputs(account_handle, &prepared_transaction, transaction_flags).await?;
+
+    if transaction_flags.dry_run {
+        log::info!(
+            "Dry run, the transaction has not been signed nor submitted:\n{}",
+            serde_json::to_string_pretty(&PreparedTransactionDataDto::from(&prepared_transaction))?
+        );
+        release_inputs(account_handle, &prepared_transaction).await?;
+        return Ok(None);
+    }
+
+    // The inputs of a transaction signed offline stay locked, so that no other transaction spends them before it is
+    // submitted.
+    if let Some(path) = &transaction_flags.prepared_transaction_path {
+        write_json_file(path, &PreparedTransactionDataDto::from(&prepared_transaction)).await?;
+        log::info!("Prepared transaction written to \"{path}\", sign it with `sign-transaction`.");
+        return Ok(None);
+    }
+
+    if !confirm_transaction(
+        account_handle,
+        &prepared_transaction,
+        transaction_flags,
+        "Sign and submit the transaction?",
+        false,
+    )
+    .await?
+    {
+        release_inputs(account_handle, &prepared_transaction).await?;
+        return Ok(None);
+    }
+
+    let transaction = account_handle.sign_and_submit_transaction(prepared_transaction).await?;
+
+    if transaction_flags.wait {
+        if let Some(block_id) = transaction.block_id {
+            wait_for_transaction(
+                account_handle,
+                &transaction.transaction_id,
+                &block_id,
+                transaction_flags.wait_timeout,
+            )
+            .await?;
+        }
+    }
+
+    Ok(Some(transaction))
+}
+
+/// Releases the inputs of a prepared transaction that won't be submitted, the wallet keeps them out of input selection
+/// until then.
+pub async fn release_inputs(
+    account_handle: &AccountHandle,
+    prepared_transaction: &PreparedTransactionData,
+) -> Result<(), Error> {
+    account_handle
+        .unlock_inputs(prepared_transaction.inputs_data.clone())
+        .await?;
+
+    Ok(())
+}
+
+/// Checks that a prepared transaction consumes the outputs chosen with `--inputs` or `--mandatory-inputs`, which not
+/// every command supports, and that it doesn't consume outputs locked with `lock-output` that weren't chosen.
+///
+/// The wallet's input selection doesn't know about locked outputs, so such a transaction is refused and its inputs are
+/// released.
+async fn check_inputs(
+    account_handle: &AccountHandle,
+    prepared_transaction: &PreparedTransactionData,
+    transaction_flags: &TransactionFlags,
+) -> Result<(), Error> {
+    let inputs = prepared_transaction
+        .inputs_data
+        .iter()
+        .map(|input| *input.output_id())
+        .collect::<HashSet<_>>();
+    let selected_inputs = transaction_flags.selected_inputs()?;
+
+    if let Some(output_id) = selected_inputs.iter().find(|output_id| !inputs.contains(output_id)) {
+        release_inputs(account_handle, prepared_transaction).await?;
+        return Err(Error::Miscellaneous(format!(
+            "the transaction doesn't consume the selected input {output_id}, this command may not support input \
+             selection"
+        )));
+    }
+
+    let locked_outputs = load_storage_file::<LockedOutputs>().await?;
+    let locked_inputs = inputs
+        .iter()
+        .filter(|output_id| {
+            locked_outputs.outputs.contains_key(&output_id.to_string()) && !selected_inputs.contains(output_id)
+        })
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+
+    if !locked_inputs.is_empty() {
+        release_inputs(account_handle, prepared_transaction).await?;
+        return Err(Error::Miscellaneous(format!(
+            "the wallet selected the locked output(s) {}, which it can't leave out: choose the inputs with `--inputs` \
+             or unlock them with `unlock-output`",
+            locked_inputs.join(", ")
+        )));
+    }
+
+    Ok(())
+}
+
+/// Polls the metadata of the block of a transaction until it is included or conflicting, or until the timeout, in
+/// seconds, then syncs the account so that the inclusion state of the transaction is updated.
+pub async fn wait_for_transaction(
+    account_handle: &AccountHandle,
+    transaction_id: &TransactionId,
+    block_id: &BlockId,
+    timeout: u64,
+) -> Result<(), Error> {
+    const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+    log::info!("Waiting for transaction {transaction_id} in block {block_id}...");
+
+    let deadline = Instant::now() + Duration::from_secs(timeout);
+    let mut conflict_reason = None;
+
+    loop {
+        let metadata = account_handle.client().get_block_metadata(block_id).await?;
+
+        match metadata.ledger_inclusion_state {
+            Some(LedgerInclusionStateDto::Included) => break,
+            Some(LedgerInclusionStateDto::Conflicting) => {
+                conflict_reason = Some(metadata.conflict_reason.map_or_else(
+                    || "unknown".to_string(),
+                    |reason| {
+                        ConflictReason::try_from(reason)
+                            .map_or_else(|_| reason.to_string(), |reason| format!("{reason:?}"))
+                    },
+                ));
+                break;
+            }
+            _ => {}
+        }
+
+        if Instant::now() >= deadline {
+            log::warn!(
+                "Transaction {transaction_id} is still pending after {timeout}s{}, wait again with `wait-transaction`.",
+                if metadata.should_reattach == Some(true) {
+                    " and its block should be reattached"
+                } else {
+                    ""
+                }
+            );
+            return Ok(());
+        }
+
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+
+    account_handle.sync(None).await?;
+
+    let inclusion_state = account_handle
+        .get_transaction(transaction_id)
+        .await
+        .map(|transaction| transaction.inclusion_state);
+
+    match conflict_reason {
+        Some(conflict_reason) => log::error!(
+            "Transaction {transaction_id} is conflicting: {conflict_reason}, inclusion state {inclusion_state:?}"
+        ),
+        None => log::info!("Transaction {transaction_id} is included, inclusion state {inclusion_state:?}"),
+    }
+
+    Ok(())
+}
+
+/// Shows a preview of a prepared transaction and asks for confirmation, unless `--yes` is given.
+///
+/// An `offline` preview doesn't query the node, so it can be shown on a machine without network access.
+pub async fn confirm_transaction(
+    account_handle: &AccountHandle,
+    prepared_transaction: &PreparedTransactionData,
+    transaction_flags: &TransactionFlags,
+    prompt: &str,
+    offline: bool,
+) -> Result<bool, Error> {
+    print_transaction_preview(account_handle, prepared_transaction, offline).await?;
+
+    if transaction_flags.yes || Confirm::new().with_prompt(prompt).default(false).interact()? {
+        Ok(true)
+    } else {
+        log::info!("Transaction discarded.");
+        Ok(false)
+    }
+}
+
+async fn print_transaction_preview(
+    account_handle: &AccountHandle,
+    prepared_transaction: &PreparedTransactionData,
+    offline: bool,
+) -> Result<(), Error> {
+    // Offline, the bech32 HRP is taken from the addresses of the account and storage deposits aren't shown.
+    let (bech32_hrp, rent_structure) = if offline {
+        let bech32_hrp = match account_handle.addresses().await?.first() {
+            Some(address) => Address::try_from_bech32(address.address().to_bech32())?.0,
+            None => return Err(Error::NoAddress),
+        };
+        (bech32_hrp, None)
+    } else {
+        (
+            account_handle.client().get_bech32_hrp().await?,
+            Some(account_handle.client().get_rent_structure().await?),
+        )
+    };
+    let account_addresses = account_addresses(account_handle).await?;
+    let address_book = load_storage_file::<AddressBook>().await?;
+    let outputs = match &prepared_transaction.essence {
+        TransactionEssence::Regular(essence) => essence.outputs(),
+    };
+    // Inputs always belong to the account, outputs only count if they are sent to one of its addresses.
+    let mut balance_change = 0i128;
+    let mut preview = String::from("Transaction preview:\nInputs:");
+
+    for input in &prepared_transaction.inputs_data {
+        balance_change -= input.output.amount() as i128;
+        preview.push_str(&format!(
+            "\n  {} ({} output): {}",
+            input.output_id(),
+            output_kind(&input.output),
+            input.output.amount()
+        ));
+    }
+
+    preview.push_str("\nOutputs:");
+
+    for output in outputs.iter() {
+        let address = output_address(output);
+        let is_own = address.map_or(false, |address| account_addresses.contains(&address));
+
+        if is_own {
+            balance_change += output.amount() as i128;
+        }
+
+        preview.push_str(&format!(
+            "\n  {} output to {}{}: {}",
+            output_kind(output),
+            address.map_or_else(
+                || "-".to_string(),
+                |address| format!("{}{}", address.to_bech32(&bech32_hrp), address_book.describe(&address))
+            ),
+            if is_own { " (own address)" } else { "" },
+            output.amount(),
+        ));
+        if let Some(rent_structure) = &rent_structure {
+            preview.push_str(&format!(", storage deposit {}", output.rent_cost(rent_structure)));
+        }
+        if let Some(native_tokens) = output.native_tokens() {
+            for native_token in native_tokens.iter() {
+                preview.push_str(&format!("\n    {}: {}", native_token.token_id(), native_token.amount()));
+            }
+        }
+    }
+
+    if let Some(remainder) = &prepared_transaction.remainder {
+        preview.push_str(&format!(
+            "\nRemainder: {} to {}",
+            remainder.output.amount(),
+            remainder.address.to_bech32(&bech32_hrp)
+        ));
+    }
+
+    preview.push_str(&format!("\nBalance change: {balance_change:+}"));
+
+    log::info!("{preview}");
+
+    Ok(())
+}
+
+/// Sets the derivation chains of the inputs of a prepared transaction from the addresses of the account.
+///
+/// A transaction prepared by a watch-only account doesn't know which keys unlock its inputs.
+pub async fn set_input_chains(
+    account_handle: &AccountHandle,
+    prepared_transaction: &mut PreparedTransactionData,
+) -> Result<(), Error> {
+    let (coin_type, account_index) = {
+        let account = account_handle.read().await;
+        (*account.coin_type(), *account.index())
+    };
+    let addresses = account_handle.addresses().await?;
+
+    for input in &mut prepared_transaction.inputs_data {
+        // Inputs unlocked by an alias or an NFT don't need a signature.
+        let Some(address @ Address::Ed25519(_)) = output_address(&input.output) else {
+            continue;
+        };
+        let account_address = addresses
+            .iter()
+            .find(|account_address| *account_address.address().as_ref() == address)
+            .ok_or_else(|| {
+                Error::Miscellaneous(format!(
+                    "input {} isn't controlled by an address of this account",
+                    input.output_id()
+                ))
+            })?;
+
+        input.chain = Some(Chain::from_u32_hardened(vec![
+            HD_WALLET_TYPE,
+            coin_type,
+            account_index,
+            *account_address.internal() as u32,
+            *account_address.key_index(),
+        ]));
+    }
+
+    Ok(())
+}
+
+/// Returns the addresses of the account.
+pub async fn account_addresses(account_handle: &AccountHandle) -> Result<HashSet<Address>, Error> {
+    Ok(account_handle
+        .addresses()
+        .await?
+        .iter()
+        .map(|address| *address.address().as_ref())
+        .collect())
+}
+
+/// Returns the address controlling an output.
+pub fn output_address(output: &Output) -> Option<Address> {
+    match output {
+        Output::Alias(alias_output) => Some(*alias_output.state_controller_address()),
+        Output::Foundry(foundry_output) => Some(Address::Alias(*foundry_output.alias_address())),
+        _ => output
+            .unlock_conditions()
+            .and_then(|unlock_conditions| unlock_conditions.address())
+            .map(|unlock_condition| *unlock_condition.address()),
+    }
+}
+
+pub fn output_kind(output: &Output) -> &'static str {
+    match output {
+        Output::Alias(_) => "alias",
+        Output::Basic(_) => "basic",
+        Output::Foundry(_) => "foundry",
+        Output::Nft(_) => "nft",
+        Output::Treasury(_) => "treasury",
+    }
+}
@@ -0,0 +1,162 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{collections::BTreeMap, str::FromStr};
+
+use clap::Parser;
+use dialoguer::{console::Term, theme::ColorfulTheme, Input, Select};
+use iota_wallet::iota_client::{
+    api_types::core::response::OutputResponse,
+    block::output::{Output, OutputId},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    command::{
+        account::AccountCli,
+        watch_only::{
+            addresses_command, balance_command, outputs_command, prepare_send_command, sync_command,
+            transactions_command, WatchOnlyAccountCommand, WatchOnlyCli,
+        },
+    },
+    error::Error,
+    helper::{save_storage_file, StorageFile},
+};
+
+/// An account tracking addresses by their bech32 encoding, without any secret to spend from them.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WatchOnlyAccount {
+    pub alias: String,
+    pub node_url: String,
+    pub addresses: Vec<String>,
+    /// Outputs of the addresses as of the last sync, indexed by output id, spent ones included.
+    #[serde(default)]
+    pub outputs: BTreeMap<String, OutputResponse>,
+}
+
+impl WatchOnlyAccount {
+    pub fn unspent_outputs(&self) -> Result<Vec<(OutputId, Output)>, Error> {
+        self.outputs
+            .iter()
+            .filter(|(_, output_response)| !output_response.metadata.is_spent)
+            .map(|(output_id, output_response)| {
+                Ok((
+                    OutputId::from_str(output_id)?,
+                    Output::try_from_dto_unverified(&output_response.output)?,
+                ))
+            })
+            .collect()
+    }
+}
+
+/// The watch-only accounts.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct WatchOnlyAccounts {
+    pub accounts: Vec<WatchOnlyAccount>,
+}
+
+impl StorageFile for WatchOnlyAccounts {
+    const FILE_NAME: &'static str = "watch-only-accounts.json";
+}
+
+impl WatchOnlyAccounts {
+    pub fn get_mut(&mut self, alias: &str) -> Result<&mut WatchOnlyAccount, Error> {
+        self.accounts
+            .iter_mut()
+            .find(|account| account.alias == alias)
+            .ok_or_else(|| Error::Miscellaneous(format!("watch-only account \"{alias}\" not found")))
+    }
+
+    pub fn pick(&self) -> Result<Option<String>, Error> {
+        match self.accounts.len() {
+            0 => Ok(None),
+            1 => Ok(Some(self.accounts[0].alias.clone())),
+            _ => {
+                let items = self.accounts.iter().map(|account| &account.alias).collect::<Vec<_>>();
+                let index = Select::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Select a watch-only account:")
+                    .items(&items)
+                    .default(0)
+                    .interact_on(&Term::stderr())?;
+
+                Ok(Some(self.accounts[index].alias.clone()))
+            }
+        }
+    }
+}
+
+// loop on the watch-only account prompt
+pub async fn watch_only_prompt(mut accounts: WatchOnlyAccounts, alias: String) -> Result<(), Error> {
+    // Fail early if the account doesn't exist.
+    accounts.get_mut(&alias)?;
+
+    loop {
+        match watch_only_prompt_internal(&mut accounts, &alias).await {
+            Ok(true) => {
+                return Ok(());
+            }
+            Err(e) => {
+                log::error!("{e}");
+            }
+            _ => {}
+        }
+    }
+}
+
+pub async fn watch_only_prompt_internal(accounts: &mut WatchOnlyAccounts, alias: &str) -> Result<bool, Error> {
+    let command: String = Input::new()
+        .with_prompt(format!("Watch-only account \"{alias}\""))
+        .interact()?;
+
+    match command.as_str() {
+        "h" => {
+            if let Err(err) = WatchOnlyCli::try_parse_from(vec!["Account:", "help"]) {
+                println!("{err}");
+            }
+        }
+        "clear" => {
+            // Clear console
+            let _ = std::process::Command::new("clear").status();
+        }
+        _ => {
+            // Prepend `Account: ` so the parsing will be correct
+            let command = format!("Account: {}", command.trim());
+            let watch_only_cli = match WatchOnlyCli::try_parse_from(command.split(' ')) {
+                Ok(watch_only_cli) => watch_only_cli,
+                Err(err) => {
+                    // Other account commands are valid, tell why they are refused.
+                    match AccountCli::try_parse_from(command.split(' ')) {
+                        Ok(account_cli) if account_cli.command.signs_transaction() => {
+                            log::error!("{}", Error::WatchOnly)
+                        }
+                        Ok(_) => log::error!("this command isn't available in watch-only accounts"),
+                        Err(_) => println!("{err}"),
+                    }
+                    return Ok(false);
+                }
+            };
+            let account = accounts.get_mut(alias)?;
+
+            if let Err(err) = match watch_only_cli.command {
+                WatchOnlyAccountCommand::Addresses => addresses_command(account),
+                WatchOnlyAccountCommand::Balance => balance_command(account).await,
+                WatchOnlyAccountCommand::Exit => {
+                    return Ok(true);
+                }
+                WatchOnlyAccountCommand::Outputs => outputs_command(account),
+                WatchOnlyAccountCommand::PrepareSend { path, address, amount } => {
+                    prepare_send_command(account, path, address, amount).await
+                }
+                WatchOnlyAccountCommand::Sync => match sync_command(account).await {
+                    Ok(()) => save_storage_file(&accounts).await,
+                    Err(err) => Err(err),
+                },
+                WatchOnlyAccountCommand::Transactions => transactions_command(account),
+            } {
+                log::error!("{}", err);
+            }
+        }
+    }
+
+    Ok(false)
+}